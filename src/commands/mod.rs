@@ -18,6 +18,9 @@
 */
 
 mod moderation;
+mod notes;
+mod purge;
+mod spam_filter;
 mod utility;
 mod fun;
 
@@ -27,13 +30,36 @@ pub fn get_commands() -> Vec<poise::Command<Data, Error>> {
     vec![
         // Moderation commands
         moderation::ban(),
+        moderation::softban(),
+        moderation::tempban(),
+        moderation::hackban(),
+        moderation::massban(),
         moderation::kick(),
         moderation::unban(),
         moderation::timeout(),
+        moderation::warn(),
+        moderation::warnings(),
+        moderation::delwarn(),
+        moderation::clearwarns(),
+        moderation::escalation(),
+        moderation::mod_log_channel(),
+        moderation::appeal_channel(),
+        moderation::ban_image(),
+        moderation::ban_template(),
+        moderation::case(),
+        moderation::reason(),
+        notes::note(),
+        moderation::profile(),
+        moderation::history(),
         moderation::clean(),
+        purge::purge(),
         moderation::mod_stats(),
+        moderation::mod_export(),
         moderation::scan_bans(),
 
+        // Spam filter commands
+        spam_filter::spam_filter(),
+
         // Utility commands
         utility::ping(),
         utility::help(),
@@ -17,10 +17,77 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use crate::database::{EscalationStep, ModAction, ModStatsFilter, TempBan};
+use crate::modules::ban_notice::{self, DEFAULT_TEMPLATE};
+use crate::modules::escalation::{self, EscalationAction};
+use crate::modules::mod_export::{self, ExportFormat};
+use crate::modules::mod_log;
+use crate::modules::stats_chart;
+use crate::modules::temp_bans;
+use crate::utils::{
+    confirm, format_duration, is_url, paginate_embeds, parse_time_bound, truncate, HumanDuration, MAX_TIMEOUT_SECS,
+};
 use crate::{Context, Error};
 use poise::serenity_prelude as serenity;
+use poise::ChoiceParameter;
 use std::collections::HashMap;
 
+const WARNINGS_PER_PAGE: usize = 10;
+const CASE_EDITS_SHOWN: usize = 5;
+const HISTORY_PER_PAGE: usize = 10;
+const MAX_MASS_BAN: usize = 1000;
+const MASS_BAN_FAILURES_SHOWN: usize = 15;
+const MAX_ID_FILE_BYTES: u32 = 1024 * 1024;
+const AUDIT_IMPORT_MAX_PAGES: usize = 50;
+const LEADERBOARD_PER_PAGE: usize = 10;
+const TREND_BUCKETS_SHOWN: usize = 12;
+// Discord's upload limit for bots without boosts
+const MAX_EXPORT_BYTES: usize = 8 * 1024 * 1024;
+const MAX_BAN_TEMPLATE_LENGTH: usize = 2000;
+
+/// Why a user can't be banned by the author or the bot, if they can't.
+/// Users who aren't in the server can always be banned.
+async fn ban_blocker(
+    ctx: Context<'_>,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+) -> Result<Option<&'static str>, Error> {
+    let bot_user_id = ctx.cache().current_user().id;
+    if user_id == ctx.author().id {
+        return Ok(Some("You can't ban yourself"));
+    }
+    if user_id == bot_user_id {
+        return Ok(Some("I can't ban myself"));
+    }
+
+    let Ok(member) = guild_id.member(ctx, user_id).await else {
+        return Ok(None);
+    };
+    let bot_member = guild_id.member(ctx, bot_user_id).await?;
+    let author_member = ctx.author_member().await.ok_or("Couldn't get your member info")?;
+
+    let guild = ctx.guild().ok_or("Guild not in cache")?;
+    let position = |m: &serenity::Member| {
+        if m.user.id == guild.owner_id {
+            u16::MAX
+        } else {
+            guild.member_highest_role(m).map_or(0, |role| role.position)
+        }
+    };
+
+    Ok(if user_id == guild.owner_id {
+        Some("The server owner can't be banned")
+    } else if !guild.member_permissions(&bot_member).ban_members() {
+        Some("I need the Ban Members permission to do that")
+    } else if position(&member) >= position(&bot_member) {
+        Some("I can't ban them, their highest role isn't below mine")
+    } else if position(&member) >= position(&author_member) {
+        Some("You can't ban someone whose highest role isn't below yours")
+    } else {
+        None
+    })
+}
+
 /// Ban a user from the server
 #[poise::command(
     slash_command,
@@ -32,170 +99,1299 @@ pub async fn ban(
     ctx: Context<'_>,
     #[description = "User to ban"] user: serenity::User,
     #[description = "Reason for the ban"] reason: Option<String>,
+    #[description = "Days of their messages to delete (0-7, default 1)"]
+    #[min = 0]
+    #[max = 7]
+    delete_days: Option<u8>,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
     let reason_str = reason.clone().unwrap_or_else(|| format!("Banned by {}", ctx.author().name));
+    let delete_days = delete_days.unwrap_or(1);
+
+    if delete_days > 7 {
+        ctx.say(":x: Can only delete up to 7 days of messages").await?;
+        return Ok(());
+    }
+
+    // Check before the notice goes out, so nobody is told about a ban that then fails
+    if let Some(blocker) = ban_blocker(ctx, guild_id, user.id).await? {
+        ctx.say(format!(":x: {}", blocker)).await?;
+        return Ok(());
+    }
+
+    ban_notice::notify_ban(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id,
+        &user,
+        ctx.author(),
+        reason.as_deref(),
+        None,
+    )
+    .await?;
+
+    // Ban the user
+    mod_log::expect_ban(guild_id, user.id).await;
+    guild_id
+        .ban_with_reason(ctx.http(), user.id, delete_days, &reason_str)
+        .await?;
+
+    // A permanent ban replaces any pending temporary one
+    ctx.data().db.remove_temp_ban(guild_id.get(), user.id.get()).await?;
+
+    // Record to database
+    let case_number = mod_log::record(
+        ctx.http(),
+        &ctx.data().db,
+        ModAction::new(guild_id.get(), ctx.author().id.get(), user.id.get(), "ban", reason.as_deref()),
+    )
+    .await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Successfully banned **{}** ({}) | Case #{}",
+        user.name, user.id, case_number
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Ban and immediately unban a user to delete their recent messages
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "BAN_MEMBERS",
+    guild_only
+)]
+pub async fn softban(
+    ctx: Context<'_>,
+    #[description = "User to softban"] user: serenity::User,
+    #[description = "Reason for the softban"] reason: Option<String>,
+    #[description = "Days of their messages to delete (1-7, default 1)"]
+    #[min = 1]
+    #[max = 7]
+    delete_days: Option<u8>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let reason_str = reason.clone().unwrap_or_else(|| format!("Softbanned by {}", ctx.author().name));
+    let delete_days = delete_days.unwrap_or(1);
+
+    if !(1..=7).contains(&delete_days) {
+        ctx.say(":x: Days of messages to delete must be between 1 and 7").await?;
+        return Ok(());
+    }
+
+    if let Some(blocker) = ban_blocker(ctx, guild_id, user.id).await? {
+        ctx.say(format!(":x: {}", blocker)).await?;
+        return Ok(());
+    }
+
+    mod_log::expect_ban(guild_id, user.id).await;
+    guild_id
+        .ban_with_reason(ctx.http(), user.id, delete_days, &reason_str)
+        .await?;
+
+    // Try the unban twice, a failure here leaves them banned for good
+    let mut unbanned = guild_id.unban(ctx.http(), user.id).await;
+    if unbanned.is_err() {
+        unbanned = guild_id.unban(ctx.http(), user.id).await;
+    }
+
+    ctx.data().db.remove_temp_ban(guild_id.get(), user.id.get()).await?;
+
+    if let Err(e) = unbanned {
+        let note = format!(
+            "{} (softban, unban failed: {})",
+            reason.as_deref().unwrap_or("No reason given"),
+            e
+        );
+        let case_number = mod_log::record(
+            ctx.http(),
+            &ctx.data().db,
+            ModAction::new(guild_id.get(), ctx.author().id.get(), user.id.get(), "ban", Some(&note)),
+        )
+        .await?;
+
+        ctx.say(format!(
+            ":x: Banned **{}** ({}) but couldn't unban them again, they're still banned. Use `/unban` to lift it | Case #{}",
+            user.name, user.id, case_number
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    // Record to database
+    let case_number = mod_log::record(
+        ctx.http(),
+        &ctx.data().db,
+        ModAction::new(guild_id.get(), ctx.author().id.get(), user.id.get(), "softban", reason.as_deref()),
+    )
+    .await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Softbanned **{}** ({}), deleting {} day(s) of messages | Case #{}",
+        user.name, user.id, delete_days, case_number
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Temporarily ban a user from the server
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "BAN_MEMBERS",
+    guild_only
+)]
+pub async fn tempban(
+    ctx: Context<'_>,
+    #[description = "User to ban"] user: serenity::User,
+    #[description = "Duration, e.g. 12h, 7d or 2w"] duration: HumanDuration,
+    #[description = "Reason for the ban"] reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    if !(60..=365 * 86400).contains(&duration.as_secs()) {
+        ctx.say(":x: Duration must be between 1 minute and 365 days")
+            .await?;
+        return Ok(());
+    }
+
+    let reason_str = reason
+        .clone()
+        .unwrap_or_else(|| format!("Temporarily banned by {}", ctx.author().name));
+    let expires_at = chrono::Utc::now().timestamp() + duration.as_secs();
+
+    // Check before the notice goes out, so nobody is told about a ban that then fails
+    if let Some(blocker) = ban_blocker(ctx, guild_id, user.id).await? {
+        ctx.say(format!(":x: {}", blocker)).await?;
+        return Ok(());
+    }
+
+    ban_notice::notify_ban(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id,
+        &user,
+        ctx.author(),
+        reason.as_deref(),
+        Some(expires_at),
+    )
+    .await?;
 
     // Ban the user
+    mod_log::expect_ban(guild_id, user.id).await;
     guild_id
         .ban_with_reason(ctx.http(), user.id, 1, &reason_str)
         .await?;
 
-    // Record to database
-    ctx.data()
+    ctx.data()
+        .db
+        .set_temp_ban(&TempBan {
+            guild_id: guild_id.get(),
+            user_id: user.id.get(),
+            moderator_id: ctx.author().id.get(),
+            expires_at,
+        })
+        .await?;
+
+    // Record to database
+    let action = ModAction {
+        duration: Some(duration.as_secs()),
+        ..ModAction::new(guild_id.get(), ctx.author().id.get(), user.id.get(), "tempban", reason.as_deref())
+    };
+    let case_number = mod_log::record(ctx.http(), &ctx.data().db, action).await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Banned **{}** ({}) until <t:{}:f> | Case #{}",
+        user.name, user.id, expires_at, case_number
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Ban users by ID, even if they aren't in the server
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "BAN_MEMBERS",
+    guild_only
+)]
+pub async fn hackban(
+    ctx: Context<'_>,
+    #[description = "User IDs or mentions, separated by spaces or commas"] ids: String,
+    #[description = "Reason for the ban"] reason: Option<String>,
+) -> Result<(), Error> {
+    let ids = parse_user_ids(&ids);
+    let needs_confirmation = ids.len() > 1;
+
+    ban_user_ids(ctx, ids, reason, needs_confirmation).await
+}
+
+/// Ban a list of user IDs, given inline or as an attached text file
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "BAN_MEMBERS",
+    guild_only
+)]
+pub async fn massban(
+    ctx: Context<'_>,
+    #[description = "User IDs or mentions, separated by spaces or commas"] ids: Option<String>,
+    #[description = "Text file with user IDs"] file: Option<serenity::Attachment>,
+    #[description = "Reason for the bans"] reason: Option<String>,
+) -> Result<(), Error> {
+    let mut input = ids.unwrap_or_default();
+
+    if let Some(file) = file {
+        if file.size > MAX_ID_FILE_BYTES {
+            ctx.say(":x: That file is too big, keep it under 1 MB").await?;
+            return Ok(());
+        }
+        let bytes = file.download().await?;
+        input.push('\n');
+        input.push_str(&String::from_utf8_lossy(&bytes));
+    }
+
+    ban_user_ids(ctx, parse_user_ids(&input), reason, true).await
+}
+
+/// Pull user IDs out of free text, keeping the first occurrence of each
+fn parse_user_ids(input: &str) -> Vec<u64> {
+    let mut ids = Vec::new();
+    for token in input.split(|c: char| !c.is_ascii_digit()) {
+        // Snowflakes are 17 to 20 digits long
+        if !(17..=20).contains(&token.len()) {
+            continue;
+        }
+        if let Ok(id) = token.parse::<u64>() {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// Ban every ID in the list, reporting progress and per-ID failures
+async fn ban_user_ids(
+    ctx: Context<'_>,
+    ids: Vec<u64>,
+    reason: Option<String>,
+    needs_confirmation: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    if ids.is_empty() {
+        ctx.say(":x: No valid user IDs given").await?;
+        return Ok(());
+    }
+    if ids.len() > MAX_MASS_BAN {
+        ctx.say(format!(":x: You can ban at most {} users at once", MAX_MASS_BAN))
+            .await?;
+        return Ok(());
+    }
+
+    let reply = if needs_confirmation {
+        let prompt = format!(
+            ":warning: About to ban **{}** user(s). Reason: {}",
+            ids.len(),
+            reason.as_deref().unwrap_or("No reason given")
+        );
+        match confirm(ctx, &prompt).await? {
+            Some(reply) => reply,
+            None => return Ok(()),
+        }
+    } else {
+        ctx.say(format!("Banning {} user(s)...", ids.len())).await?
+    };
+
+    let reason_str = reason.clone().unwrap_or_else(|| format!("Banned by {}", ctx.author().name));
+    let mut banned = 0;
+    let mut failures: Vec<(u64, String)> = Vec::new();
+
+    for (i, id) in ids.iter().enumerate() {
+        let user_id = serenity::UserId::new(*id);
+        let result = match ban_blocker(ctx, guild_id, user_id).await {
+            Ok(Some(blocker)) => Err(blocker.to_string()),
+            Err(e) => Err(e.to_string()),
+            Ok(None) => {
+                mod_log::expect_ban(guild_id, user_id).await;
+                guild_id
+                    .ban_with_reason(ctx.http(), user_id, 1, &reason_str)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                banned += 1;
+                ctx.data().db.remove_temp_ban(guild_id.get(), *id).await?;
+                // One row per ban, the mod-log gets a single summary below
+                ctx.data()
+                    .db
+                    .add_mod_action(&ModAction::new(
+                        guild_id.get(),
+                        ctx.author().id.get(),
+                        *id,
+                        "ban",
+                        reason.as_deref(),
+                    ))
+                    .await?;
+            }
+            Err(e) => failures.push((*id, e)),
+        }
+
+        if (i + 1) % 10 == 0 && i + 1 < ids.len() {
+            let _ = reply
+                .edit(
+                    ctx,
+                    poise::CreateReply::default().content(format!(
+                        "Banning... {}/{} done ({} failed)",
+                        i + 1,
+                        ids.len(),
+                        failures.len()
+                    )),
+                )
+                .await;
+        }
+    }
+
+    let mut summary = format!(":white_check_mark: Banned {}/{} user(s)", banned, ids.len());
+    if !failures.is_empty() {
+        summary.push_str(&format!("\n:x: {} failed:", failures.len()));
+        for (id, error) in failures.iter().take(MASS_BAN_FAILURES_SHOWN) {
+            summary.push_str(&format!("\n`{}` - {}", id, truncate(error, 100)));
+        }
+        if failures.len() > MASS_BAN_FAILURES_SHOWN {
+            summary.push_str(&format!("\n...and {} more", failures.len() - MASS_BAN_FAILURES_SHOWN));
+        }
+    }
+    reply
+        .edit(ctx, poise::CreateReply::default().content(summary))
+        .await?;
+
+    // Full per-ID results when they don't fit in the summary
+    if failures.len() > MASS_BAN_FAILURES_SHOWN {
+        let results: String = ids
+            .iter()
+            .map(|id| match failures.iter().find(|(failed, _)| failed == id) {
+                Some((_, error)) => format!("{}\tfailed: {}\n", id, error),
+                None => format!("{}\tbanned\n", id),
+            })
+            .collect();
+        ctx.send(
+            poise::CreateReply::default()
+                .attachment(serenity::CreateAttachment::bytes(results.into_bytes(), "ban-results.txt")),
+        )
+        .await?;
+    }
+
+    if banned > 0 {
+        let embed = serenity::CreateEmbed::new()
+            .title("Mass ban")
+            .color(0xff0000)
+            .field("Moderator", format!("<@{}>", ctx.author().id), true)
+            .field("Banned", format!("{}/{}", banned, ids.len()), true)
+            .field(
+                "Reason",
+                truncate(reason.as_deref().unwrap_or("No reason given"), 1000),
+                false,
+            )
+            .timestamp(chrono::Utc::now());
+        mod_log::post(ctx.http(), &ctx.data().db, guild_id.get(), embed).await?;
+    }
+
+    Ok(())
+}
+
+/// Kick a user from the server
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "KICK_MEMBERS",
+    guild_only
+)]
+pub async fn kick(
+    ctx: Context<'_>,
+    #[description = "User to kick"] user: serenity::User,
+    #[description = "Reason for the kick"] reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let reason_str = reason.clone().unwrap_or_else(|| format!("Kicked by {}", ctx.author().name));
+
+    // Get the member
+    let member = guild_id.member(ctx.http(), user.id).await?;
+
+    // Kick the user
+    member.kick_with_reason(ctx.http(), &reason_str).await?;
+
+    // Record to database
+    let case_number = mod_log::record(
+        ctx.http(),
+        &ctx.data().db,
+        ModAction::new(guild_id.get(), ctx.author().id.get(), user.id.get(), "kick", reason.as_deref()),
+    )
+    .await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Successfully kicked **{}** ({}) | Case #{}",
+        user.name, user.id, case_number
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Unban a user from the server
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "BAN_MEMBERS",
+    guild_only
+)]
+pub async fn unban(
+    ctx: Context<'_>,
+    #[description = "User ID to unban"] user_id: String,
+    #[description = "Reason for the unban"] reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let user_id: u64 = user_id.parse().map_err(|_| "Invalid user ID")?;
+
+    let case_number = temp_bans::lift_ban(
+        ctx.http(),
+        &ctx.data().db,
+        guild_id,
+        serenity::UserId::new(user_id),
+        ctx.author().id.get(),
+        reason.as_deref(),
+    )
+    .await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Successfully unbanned user {} | Case #{}",
+        user_id, case_number
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Timeout a user
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only
+)]
+pub async fn timeout(
+    ctx: Context<'_>,
+    #[description = "User to timeout"] user: serenity::User,
+    #[description = "Duration, e.g. 30m, 2h or 1d"] duration: HumanDuration,
+    #[description = "Reason for the timeout"] reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    if !(60..=MAX_TIMEOUT_SECS).contains(&duration.as_secs()) {
+        ctx.say(":x: Duration must be between 1 minute and 28 days")
+            .await?;
+        return Ok(());
+    }
+
+    let mut member = guild_id.member(ctx.http(), user.id).await?;
+
+    // Calculate timeout end time
+    let timeout_until = chrono::Utc::now() + duration.as_chrono();
+
+    // Apply timeout
+    member
+        .disable_communication_until_datetime(ctx.http(), timeout_until.into())
+        .await?;
+
+    // Record to database
+    let action = ModAction {
+        duration: Some(duration.as_secs()),
+        ..ModAction::new(guild_id.get(), ctx.author().id.get(), user.id.get(), "timeout", reason.as_deref())
+    };
+    let case_number = mod_log::record(ctx.http(), &ctx.data().db, action).await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Timed out **{}** for {} | Case #{}",
+        user.name, duration, case_number
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Warn a user
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only
+)]
+pub async fn warn(
+    ctx: Context<'_>,
+    #[description = "User to warn"] user: serenity::User,
+    #[description = "Reason for the warning"]
+    #[rest]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let (warning_id, count) = escalation::record_warning(
+        ctx.http(),
+        ctx.data(),
+        guild_id.get(),
+        user.id.get(),
+        ctx.author().id.get(),
+        reason.as_deref(),
+    )
+    .await?;
+
+    // Let the user know, they may have DMs closed
+    let guild_name = ctx
+        .guild()
+        .map(|g| g.name.clone())
+        .unwrap_or_else(|| "the server".to_string());
+    let _ = user
+        .dm(
+            ctx.http(),
+            serenity::CreateMessage::new().embed(
+                serenity::CreateEmbed::new()
+                    .title(format!("You have been warned in {}", guild_name))
+                    .description(format!(
+                        "Reason: {}\nYou now have {} warning(s).",
+                        reason.as_deref().unwrap_or("No reason given"),
+                        count
+                    ))
+                    .color(0xffa500),
+            ),
+        )
+        .await;
+
+    let mut response = format!(
+        ":white_check_mark: Warned **{}** (warning ID {}, {} total)",
+        user.name, warning_id, count
+    );
+
+    match escalation::escalate(ctx.serenity_context(), ctx.data(), guild_id, &user, count).await {
+        Ok(Some(step)) => response.push_str(&format!(
+            "\n:rotating_light: Escalation at {} warnings: {}",
+            step.warn_count,
+            escalation::describe_step(&step)
+        )),
+        Ok(None) => {}
+        Err(e) => response.push_str(&format!("\n:x: Escalation failed: {}", e)),
+    }
+
+    ctx.say(response).await?;
+
+    Ok(())
+}
+
+/// Show a user's warnings
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only
+)]
+pub async fn warnings(
+    ctx: Context<'_>,
+    #[description = "User to show warnings for"] user: serenity::User,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let warnings = ctx.data().db.get_warnings(guild_id.get(), user.id.get()).await?;
+
+    if warnings.is_empty() {
+        ctx.say(format!("**{}** has no warnings.", user.name)).await?;
+        return Ok(());
+    }
+
+    let pages: Vec<_> = warnings
+        .chunks(WARNINGS_PER_PAGE)
+        .map(|chunk| {
+            let mut description = String::new();
+            for warning in chunk {
+                description.push_str(&format!(
+                    "**ID {}** - <t:{}:f> by <@{}>\n{}\n\n",
+                    warning.id,
+                    warning.timestamp,
+                    warning.moderator_id,
+                    truncate(warning.reason.as_deref().unwrap_or("No reason given"), 300)
+                ));
+            }
+
+            serenity::CreateEmbed::new()
+                .title(format!("Warnings for {} ({})", user.name, warnings.len()))
+                .thumbnail(user.face())
+                .color(0xffa500)
+                .description(description)
+        })
+        .collect();
+
+    paginate_embeds(ctx, &pages).await?;
+
+    Ok(())
+}
+
+/// Delete a warning by ID
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only
+)]
+pub async fn delwarn(
+    ctx: Context<'_>,
+    #[description = "Warning ID"] id: i64,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let warning = match ctx.data().db.get_warning(guild_id.get(), id).await? {
+        Some(w) => w,
+        None => {
+            ctx.say(format!(":x: No warning with ID {} in this server", id)).await?;
+            return Ok(());
+        }
+    };
+
+    ctx.data().db.delete_warning(guild_id.get(), id).await?;
+
+    // Record to database
+    mod_log::record(
+        ctx.http(),
+        &ctx.data().db,
+        ModAction::new(
+            guild_id.get(),
+            ctx.author().id.get(),
+            warning.user_id,
+            "delwarn",
+            Some(&format!("Deleted warning {}", id)),
+        ),
+    )
+    .await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Deleted warning {} for <@{}>",
+        id, warning.user_id
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Clear all of a user's warnings
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only
+)]
+pub async fn clearwarns(
+    ctx: Context<'_>,
+    #[description = "User to clear warnings for"] user: serenity::User,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let cleared = ctx.data().db.clear_warnings(guild_id.get(), user.id.get()).await?;
+
+    if cleared == 0 {
+        ctx.say(format!("**{}** has no warnings.", user.name)).await?;
+        return Ok(());
+    }
+
+    // Record to database
+    mod_log::record(
+        ctx.http(),
+        &ctx.data().db,
+        ModAction::new(
+            guild_id.get(),
+            ctx.author().id.get(),
+            user.id.get(),
+            "clearwarns",
+            Some(&format!("Cleared {} warning(s)", cleared)),
+        ),
+    )
+    .await?;
+
+    ctx.say(format!(
+        ":white_check_mark: Cleared {} warning(s) for **{}**",
+        cleared, user.name
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Set the channel moderation actions are posted to
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "mod-log",
+    required_permissions = "MANAGE_GUILD",
+    guild_only
+)]
+pub async fn mod_log_channel(
+    ctx: Context<'_>,
+    #[description = "Channel to post to, leave empty to disable"] channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    ctx.data()
+        .db
+        .set_mod_log_channel(guild_id.get(), channel.as_ref().map(|c| c.id.get()))
+        .await?;
+
+    match channel {
+        Some(channel) => {
+            ctx.say(format!(":white_check_mark: Moderation actions will be posted in <#{}>", channel.id))
+                .await?
+        }
+        None => ctx.say(":white_check_mark: Mod-log disabled").await?,
+    };
+
+    Ok(())
+}
+
+/// Set the channel ban appeals are posted to
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "appeal-channel",
+    required_permissions = "MANAGE_GUILD",
+    guild_only
+)]
+pub async fn appeal_channel(
+    ctx: Context<'_>,
+    #[description = "Channel to post appeals to, leave empty to stop taking appeals"]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    ctx.data()
+        .db
+        .set_appeal_channel(guild_id.get(), channel.as_ref().map(|c| c.id.get()))
+        .await?;
+
+    match channel {
+        Some(channel) => {
+            ctx.say(format!(
+                ":white_check_mark: Ban appeals will be posted in <#{}>, banned users are told how to appeal",
+                channel.id
+            ))
+            .await?
+        }
+        None => ctx.say(":white_check_mark: Ban appeals disabled").await?,
+    };
+
+    Ok(())
+}
+
+/// Whether the author may change server-wide settings, for options that need more than the command itself
+async fn author_can_manage_guild(ctx: Context<'_>) -> bool {
+    let Some(member) = ctx.author_member().await else {
+        return false;
+    };
+    ctx.guild().is_some_and(|g| g.member_permissions(&member).manage_guild())
+}
+
+/// Manage the image shown in ban DMs
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "ban-image",
+    required_permissions = "BAN_MEMBERS",
+    guild_only,
+    subcommands("ban_image_set", "ban_image_clear")
+)]
+pub async fn ban_image(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set the image shown to users you ban, or the server's default
+#[poise::command(slash_command, prefix_command, rename = "set")]
+pub async fn ban_image_set(
+    ctx: Context<'_>,
+    #[description = "Image URL"] url: String,
+    #[description = "Set the server default instead of your own (needs Manage Server)"] server: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let server = server.unwrap_or(false);
+
+    if !is_url(&url) {
+        ctx.say(":x: The image must be an http(s) URL").await?;
+        return Ok(());
+    }
+    if server && !author_can_manage_guild(ctx).await {
+        ctx.say(":x: You need the Manage Server permission to set the server default").await?;
+        return Ok(());
+    }
+
+    let banner = (!server).then(|| ctx.author().id.get());
+    ctx.data().db.set_ban_image(guild_id.get(), banner, &url).await?;
+
+    if server {
+        ctx.say(":white_check_mark: Set the server's default ban image").await?;
+    } else {
+        ctx.say(":white_check_mark: Set your ban image").await?;
+    }
+
+    Ok(())
+}
+
+/// Remove your ban image, or the server's default
+#[poise::command(slash_command, prefix_command, rename = "clear")]
+pub async fn ban_image_clear(
+    ctx: Context<'_>,
+    #[description = "Clear the server default instead of your own (needs Manage Server)"] server: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let server = server.unwrap_or(false);
+
+    if server && !author_can_manage_guild(ctx).await {
+        ctx.say(":x: You need the Manage Server permission to clear the server default").await?;
+        return Ok(());
+    }
+
+    let banner = (!server).then(|| ctx.author().id.get());
+    if ctx.data().db.remove_ban_image(guild_id.get(), banner).await? {
+        ctx.say(":white_check_mark: Ban image cleared").await?;
+    } else {
+        ctx.say(":x: No ban image was set").await?;
+    }
+
+    Ok(())
+}
+
+/// Set the message sent to banned users
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "ban-template",
+    required_permissions = "MANAGE_GUILD",
+    guild_only
+)]
+pub async fn ban_template(
+    ctx: Context<'_>,
+    #[description = "Message with ${guild}, ${user}, ${moderator} and ${reason}, leave empty to reset"]
+    #[rest]
+    template: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    if template.as_ref().is_some_and(|t| t.chars().count() > MAX_BAN_TEMPLATE_LENGTH) {
+        ctx.say(format!(":x: Templates can be at most {} characters", MAX_BAN_TEMPLATE_LENGTH))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.data()
+        .db
+        .set_ban_dm_template(guild_id.get(), template.as_deref())
+        .await?;
+
+    let guild_name = ctx.guild().map(|g| g.name.clone()).unwrap_or_default();
+    let preview = ban_notice::render_template(
+        template.as_deref().unwrap_or(DEFAULT_TEMPLATE),
+        &guild_name,
+        &ctx.author().name,
+        &ctx.author().name,
+        "Example reason",
+    );
+    let status = if template.is_some() {
+        ":white_check_mark: Ban DM template set"
+    } else {
+        ":white_check_mark: Ban DM template reset to the default"
+    };
+
+    ctx.send(
+        poise::CreateReply::default().content(status).embed(
+            serenity::CreateEmbed::new()
+                .title("Preview")
+                .color(0xff0000)
+                .description(truncate(&preview, 4000)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// View a moderation case
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only
+)]
+pub async fn case(
+    ctx: Context<'_>,
+    #[description = "Case number"] number: i64,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let case = match ctx.data().db.get_mod_case(guild_id.get(), number).await? {
+        Some(c) => c,
+        None => {
+            ctx.say(format!(":x: No case #{} in this server", number)).await?;
+            return Ok(());
+        }
+    };
+    let edits = ctx.data().db.get_mod_case_edits(guild_id.get(), number).await?;
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("Case #{} | {}", case.case_number, case.action))
+        .color(0xff69b4)
+        .field("User", format!("<@{}> ({})", case.target_id, case.target_id), true)
+        .field("Moderator", format!("<@{}>", case.moderator_id), true)
+        .field("Date", format!("<t:{}:f>", case.timestamp), true)
+        .field(
+            "Reason",
+            truncate(case.reason.as_deref().unwrap_or("No reason given"), 1000),
+            false,
+        );
+
+    if let Some(duration) = case.duration {
+        embed = embed.field("Duration", format_duration(duration), true);
+    }
+
+    if !edits.is_empty() {
+        let mut history = String::new();
+        for edit in edits.iter().rev().take(CASE_EDITS_SHOWN) {
+            history.push_str(&format!(
+                "<t:{}:f> by <@{}>: {}\n",
+                edit.timestamp,
+                edit.editor_id,
+                truncate(edit.old_reason.as_deref().unwrap_or("No reason given"), 100)
+            ));
+        }
+        embed = embed.field(
+            format!("Previous reasons ({} edit(s))", edits.len()),
+            history,
+            false,
+        );
+    }
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Change the reason of a moderation case
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only
+)]
+pub async fn reason(
+    ctx: Context<'_>,
+    #[description = "Case number"] number: i64,
+    #[description = "New reason"]
+    #[rest]
+    reason: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let updated = ctx
+        .data()
         .db
-        .add_mod_action(
-            guild_id.get(),
-            ctx.author().id.get(),
-            user.id.get(),
-            "ban",
-            reason.as_deref(),
-            chrono::Utc::now().timestamp(),
-        )
+        .update_mod_case_reason(guild_id.get(), number, ctx.author().id.get(), &reason)
         .await?;
 
-    ctx.say(format!(
-        ":white_check_mark: Successfully banned **{}** ({})",
-        user.name, user.id
-    ))
-    .await?;
+    if updated {
+        ctx.say(format!(":white_check_mark: Updated the reason for case #{}", number))
+            .await?;
+    } else {
+        ctx.say(format!(":x: No case #{} in this server", number)).await?;
+    }
 
     Ok(())
 }
 
-/// Kick a user from the server
+/// Show a user's moderation profile
 #[poise::command(
     slash_command,
     prefix_command,
-    required_permissions = "KICK_MEMBERS",
+    required_permissions = "MODERATE_MEMBERS",
     guild_only
 )]
-pub async fn kick(
+pub async fn profile(
     ctx: Context<'_>,
-    #[description = "User to kick"] user: serenity::User,
-    #[description = "Reason for the kick"] reason: Option<String>,
+    #[description = "User to show the profile of"] user: serenity::User,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
-    let reason_str = reason.clone().unwrap_or_else(|| format!("Kicked by {}", ctx.author().name));
+    let db = &ctx.data().db;
 
-    // Get the member
-    let member = guild_id.member(ctx.http(), user.id).await?;
+    let case_counts = db.count_user_mod_actions(guild_id.get(), user.id.get()).await?;
+    let warnings = db.count_warnings(guild_id.get(), user.id.get()).await?;
+    let notes = db.count_user_notes(guild_id.get(), user.id.get()).await?;
+    let member = guild_id.member(ctx, user.id).await.ok();
 
-    // Kick the user
-    member.kick_with_reason(ctx.http(), &reason_str).await?;
+    let total_cases: i64 = case_counts.iter().map(|(_, count)| count).sum();
+    let cases = if case_counts.is_empty() {
+        "None".to_string()
+    } else {
+        case_counts
+            .iter()
+            .map(|(action, count)| format!("{} {}", action, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
 
-    // Record to database
-    ctx.data()
-        .db
-        .add_mod_action(
-            guild_id.get(),
-            ctx.author().id.get(),
-            user.id.get(),
-            "kick",
-            reason.as_deref(),
-            chrono::Utc::now().timestamp(),
-        )
-        .await?;
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("Moderation profile for {}", user.name))
+        .thumbnail(user.face())
+        .color(0xff69b4)
+        .field("User", format!("<@{}> ({})", user.id, user.id), false)
+        .field(
+            "Account created",
+            format!("<t:{}:R>", user.id.created_at().unix_timestamp()),
+            true,
+        );
 
-    ctx.say(format!(
-        ":white_check_mark: Successfully kicked **{}** ({})",
-        user.name, user.id
-    ))
-    .await?;
+    embed = match member.as_ref().and_then(|m| m.joined_at) {
+        Some(joined_at) => embed.field("Joined", format!("<t:{}:R>", joined_at.unix_timestamp()), true),
+        None => embed.field("Joined", "Not in the server", true),
+    };
+
+    embed = embed
+        .field(format!("Cases ({})", total_cases), cases, false)
+        .field("Warnings", warnings.to_string(), true)
+        .field("Notes", format!("{} (`/note list`)", notes), true);
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
 
     Ok(())
 }
 
-/// Unban a user from the server
+/// Show a user's full moderation history
 #[poise::command(
     slash_command,
     prefix_command,
-    required_permissions = "BAN_MEMBERS",
+    required_permissions = "MODERATE_MEMBERS",
     guild_only
 )]
-pub async fn unban(
+pub async fn history(
     ctx: Context<'_>,
-    #[description = "User ID to unban"] user_id: String,
-    #[description = "Reason for the unban"] reason: Option<String>,
+    #[description = "User to show the history of"] user: serenity::User,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
-    let user_id: u64 = user_id.parse().map_err(|_| "Invalid user ID")?;
+    let db = &ctx.data().db;
 
-    // Unban the user
-    guild_id.unban(ctx.http(), serenity::UserId::new(user_id)).await?;
+    ctx.defer().await?;
 
-    // Record to database
-    ctx.data()
-        .db
-        .add_mod_action(
-            guild_id.get(),
-            ctx.author().id.get(),
-            user_id,
-            "unban",
-            reason.as_deref(),
-            chrono::Utc::now().timestamp(),
-        )
-        .await?;
+    let cases = db.get_user_mod_actions(guild_id.get(), user.id.get()).await?;
+    let warnings = db.get_warnings(guild_id.get(), user.id.get()).await?;
+    let notes = db.get_user_notes(guild_id.get(), user.id.get()).await?;
+    let temp_ban = db.get_temp_ban(guild_id.get(), user.id.get()).await?;
+    let member = guild_id.member(ctx, user.id).await.ok();
+    // Needs Ban Members, leave the state unknown without it
+    let ban = ctx.http().get_ban(guild_id, user.id).await;
 
-    ctx.say(format!(":white_check_mark: Successfully unbanned user {}", user_id))
-        .await?;
+    let now = serenity::Timestamp::now();
+    let mut state = Vec::new();
+    match (&ban, &temp_ban) {
+        (Ok(Some(_)), Some(temp_ban)) => state.push(format!("Banned until <t:{}:f>", temp_ban.expires_at)),
+        (Ok(Some(_)), None) => state.push("Banned".to_string()),
+        (Ok(None), _) => {}
+        (Err(_), _) => state.push("Ban state unknown (missing Ban Members permission)".to_string()),
+    }
+    if let Some(until) = member
+        .as_ref()
+        .and_then(|m| m.communication_disabled_until)
+        .filter(|until| *until > now)
+    {
+        state.push(format!("Timed out until <t:{}:f>", until.unix_timestamp()));
+    }
+    if state.is_empty() {
+        state.push("No active punishment".to_string());
+    }
+
+    let joined = match member.as_ref().and_then(|m| m.joined_at) {
+        Some(joined_at) => format!("<t:{}:f> (<t:{}:R>)", joined_at.unix_timestamp(), joined_at.unix_timestamp()),
+        None => "Not in the server".to_string(),
+    };
+    let created_at = user.id.created_at().unix_timestamp();
+
+    let page = |title: &str| {
+        serenity::CreateEmbed::new()
+            .title(format!("{} | {}", title, user.name))
+            .thumbnail(user.face())
+            .color(0xff69b4)
+    };
+
+    let mut pages = vec![page("Moderation history")
+        .field("User", format!("<@{}> ({})", user.id, user.id), false)
+        .field("Account created", format!("<t:{}:f> (<t:{}:R>)", created_at, created_at), true)
+        .field("Joined", joined, true)
+        .field("Status", state.join("\n"), false)
+        .field("Cases", cases.len().to_string(), true)
+        .field("Warnings", warnings.len().to_string(), true)
+        .field("Notes", notes.len().to_string(), true)];
+
+    for chunk in cases.chunks(HISTORY_PER_PAGE) {
+        let mut description = String::new();
+        for case in chunk {
+            let duration = case
+                .duration
+                .map(|d| format!(" for {}", format_duration(d)))
+                .unwrap_or_default();
+            description.push_str(&format!(
+                "**#{}** {}{} - <t:{}:d> by {}\n{}\n\n",
+                case.case_number,
+                case.action,
+                duration,
+                case.timestamp,
+                describe_moderator(&case.moderator_id.to_string()),
+                truncate(case.reason.as_deref().unwrap_or("No reason given"), 200)
+            ));
+        }
+        pages.push(page(&format!("Cases ({})", cases.len())).description(description));
+    }
+
+    for chunk in warnings.chunks(HISTORY_PER_PAGE) {
+        let mut description = String::new();
+        for warning in chunk {
+            description.push_str(&format!(
+                "**ID {}** - <t:{}:d> by <@{}>\n{}\n\n",
+                warning.id,
+                warning.timestamp,
+                warning.moderator_id,
+                truncate(warning.reason.as_deref().unwrap_or("No reason given"), 200)
+            ));
+        }
+        pages.push(page(&format!("Warnings ({})", warnings.len())).description(description));
+    }
+
+    for chunk in notes.chunks(HISTORY_PER_PAGE) {
+        let mut description = String::new();
+        for note in chunk {
+            description.push_str(&format!(
+                "**ID {}** - <t:{}:d> by <@{}>\n{}\n\n",
+                note.id,
+                note.timestamp,
+                note.author_id,
+                truncate(&note.content, 200)
+            ));
+        }
+        pages.push(page(&format!("Notes ({})", notes.len())).description(description));
+    }
+
+    paginate_embeds(ctx, &pages).await?;
 
     Ok(())
 }
 
-/// Timeout a user
+/// Manage automatic punishments for repeated warnings
 #[poise::command(
     slash_command,
     prefix_command,
-    required_permissions = "MODERATE_MEMBERS",
-    guild_only
+    required_permissions = "MANAGE_GUILD",
+    guild_only,
+    subcommands("escalation_set", "escalation_remove", "escalation_list")
 )]
-pub async fn timeout(
+pub async fn escalation(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set the punishment applied when a user reaches a warning count
+#[poise::command(slash_command, prefix_command, rename = "set")]
+pub async fn escalation_set(
     ctx: Context<'_>,
-    #[description = "User to timeout"] user: serenity::User,
-    #[description = "Duration in minutes"] duration: i64,
-    #[description = "Reason for the timeout"] reason: Option<String>,
+    #[description = "Warning count that triggers this step"]
+    #[min = 1]
+    warnings: i64,
+    #[description = "Action to take"] action: EscalationAction,
+    #[description = "Timeout duration, e.g. 1h (timeout only, default 1h)"] duration: Option<HumanDuration>,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
 
-    if duration < 1 || duration > 40320 {
-        ctx.say(":x: Duration must be between 1 and 40320 minutes (28 days)")
-            .await?;
+    if warnings < 1 {
+        ctx.say(":x: Warning count must be at least 1").await?;
         return Ok(());
     }
 
-    let mut member = guild_id.member(ctx.http(), user.id).await?;
-
-    // Calculate timeout end time
-    let timeout_until = chrono::Utc::now() + chrono::Duration::minutes(duration);
-
-    // Apply timeout
-    member
-        .disable_communication_until_datetime(ctx.http(), timeout_until.into())
-        .await?;
+    let duration_minutes = match action {
+        EscalationAction::Timeout => {
+            let duration = duration.map_or(escalation::DEFAULT_TIMEOUT_MINUTES, |d| d.as_minutes());
+            if !(1..=MAX_TIMEOUT_SECS / 60).contains(&duration) {
+                ctx.say(":x: Duration must be between 1 minute and 28 days")
+                    .await?;
+                return Ok(());
+            }
+            Some(duration)
+        }
+        _ => None,
+    };
 
-    // Record to database
-    ctx.data()
-        .db
-        .add_mod_action(
-            guild_id.get(),
-            ctx.author().id.get(),
-            user.id.get(),
-            "timeout",
-            reason.as_deref(),
-            chrono::Utc::now().timestamp(),
-        )
-        .await?;
+    let step = EscalationStep {
+        guild_id: guild_id.get(),
+        warn_count: warnings,
+        action: action.name().to_string(),
+        duration_minutes,
+    };
+    ctx.data().db.set_escalation_step(&step).await?;
 
     ctx.say(format!(
-        ":white_check_mark: Timed out **{}** for {} minutes",
-        user.name, duration
+        ":white_check_mark: At {} warning(s), users will get: {}",
+        warnings,
+        escalation::describe_step(&step)
     ))
     .await?;
 
     Ok(())
 }
 
+/// Remove an escalation step
+#[poise::command(slash_command, prefix_command, rename = "remove")]
+pub async fn escalation_remove(
+    ctx: Context<'_>,
+    #[description = "Warning count of the step to remove"] warnings: i64,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    if ctx.data().db.remove_escalation_step(guild_id.get(), warnings).await? {
+        ctx.say(format!(":white_check_mark: Removed the step at {} warning(s)", warnings))
+            .await?;
+    } else {
+        ctx.say(format!(":x: There is no step at {} warning(s)", warnings)).await?;
+    }
+
+    Ok(())
+}
+
+/// List the escalation ladder
+#[poise::command(slash_command, prefix_command, rename = "list")]
+pub async fn escalation_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let steps = ctx.data().db.get_escalation_steps(guild_id.get()).await?;
+
+    if steps.is_empty() {
+        ctx.say("No escalation steps configured for this server.").await?;
+    } else {
+        let mut message = String::from("**Escalation ladder:**\n");
+        for step in &steps {
+            message.push_str(&format!(
+                "• **{} warning(s)** - {}\n",
+                step.warn_count,
+                escalation::describe_step(step)
+            ));
+        }
+        ctx.say(message).await?;
+    }
+
+    Ok(())
+}
+
 /// Clean messages from a channel
 #[poise::command(
     slash_command,
@@ -233,6 +1429,23 @@ pub async fn clean(
     Ok(())
 }
 
+/// How `mod_stats` groups its activity trend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum TrendPeriod {
+    #[name = "weekly"]
+    Weekly,
+    #[name = "monthly"]
+    Monthly,
+}
+
+/// Name a moderator in stats, imported actions have no known moderator
+fn describe_moderator(moderator_id: &str) -> String {
+    match moderator_id {
+        "0" => "Unknown (imported)".to_string(),
+        id => format!("<@{}>", id),
+    }
+}
+
 /// Show moderator statistics
 #[poise::command(
     slash_command,
@@ -240,14 +1453,46 @@ pub async fn clean(
     required_permissions = "MANAGE_ROLES",
     guild_only
 )]
-pub async fn mod_stats(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn mod_stats(
+    ctx: Context<'_>,
+    #[description = "Start date (YYYY-MM-DD) or how long ago, e.g. 30d"] since: Option<String>,
+    #[description = "End date (YYYY-MM-DD) or how long ago, e.g. 7d"] until: Option<String>,
+    #[description = "Only count this moderator's actions"] moderator: Option<serenity::User>,
+    #[description = "Group the activity trend by week or month"] trend: Option<TrendPeriod>,
+    #[description = "Attach a chart of daily actions and top moderators"] chart: Option<bool>,
+) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
-    let guild = guild_id.to_partial_guild(ctx.http()).await?;
+
+    let mut filter = ModStatsFilter {
+        moderator_id: moderator.as_ref().map(|m| m.id.get()),
+        monthly: trend == Some(TrendPeriod::Monthly),
+        ..Default::default()
+    };
+    if let Some(since) = since {
+        match parse_time_bound(&since, false) {
+            Some(timestamp) => filter.since = Some(timestamp),
+            None => {
+                ctx.say(":x: `since` must be a date like 2025-01-31 or a duration like 30d").await?;
+                return Ok(());
+            }
+        }
+    }
+    if let Some(until) = until {
+        match parse_time_bound(&until, true) {
+            Some(timestamp) => filter.until = Some(timestamp),
+            None => {
+                ctx.say(":x: `until` must be a date like 2025-01-31 or a duration like 7d").await?;
+                return Ok(());
+            }
+        }
+    }
 
     ctx.defer().await?;
 
+    let guild = guild_id.to_partial_guild(ctx.http()).await?;
+
     // Get stats from database
-    let stats = ctx.data().db.get_mod_stats(guild_id.get()).await?;
+    let stats = ctx.data().db.get_mod_stats(guild_id.get(), &filter).await?;
 
     // Build action totals
     let mut action_totals: HashMap<String, i64> = HashMap::new();
@@ -255,6 +1500,26 @@ pub async fn mod_stats(ctx: Context<'_>) -> Result<(), Error> {
         action_totals.insert(action.clone(), *count);
     }
 
+    // Per-moderator breakdown, most common actions first
+    let mut mod_breakdowns: HashMap<&str, Vec<String>> = HashMap::new();
+    for (mod_id, action, count) in &stats.mod_counts {
+        mod_breakdowns
+            .entry(mod_id.as_str())
+            .or_default()
+            .push(format!("{} {}", action, count));
+    }
+
+    let mut scope = Vec::new();
+    if let Some(moderator) = &moderator {
+        scope.push(format!("**Moderator:** {}", moderator.name));
+    }
+    match (filter.since, filter.until) {
+        (Some(since), Some(until)) => scope.push(format!("**Period:** <t:{}:d> to <t:{}:d>", since, until)),
+        (Some(since), None) => scope.push(format!("**Since:** <t:{}:d>", since)),
+        (None, Some(until)) => scope.push(format!("**Until:** <t:{}:d>", until)),
+        (None, None) => {}
+    }
+
     // Build embed
     let mut embed = serenity::CreateEmbed::new()
         .title(format!("Moderator Statistics for {}", guild.name))
@@ -262,55 +1527,160 @@ pub async fn mod_stats(ctx: Context<'_>) -> Result<(), Error> {
         .timestamp(chrono::Utc::now());
 
     // Add overview
-    embed = embed.field(
-        "Overview",
-        format!(
-            "**Total Tracked Actions:** {}",
-            stats.total
-        ),
-        false,
-    );
+    scope.push(format!("**Total Tracked Actions:** {}", stats.total));
+    embed = embed.field("Overview", scope.join("\n"), false);
 
     // Add action breakdown
     if stats.total > 0 {
         embed = embed.field(
             "Action Breakdown",
             format!(
-                "**Bans:** {}\n**Unbans:** {}\n**Kicks:** {}\n**Timeouts:** {}",
+                "**Bans:** {}\n**Temp bans:** {}\n**Softbans:** {}\n**Unbans:** {}\n**Kicks:** {}\n**Timeouts:** {}\n**Warnings:** {}",
                 action_totals.get("ban").unwrap_or(&0),
+                action_totals.get("tempban").unwrap_or(&0),
+                action_totals.get("softban").unwrap_or(&0),
                 action_totals.get("unban").unwrap_or(&0),
                 action_totals.get("kick").unwrap_or(&0),
                 action_totals.get("timeout").unwrap_or(&0),
+                action_totals.get("warn").unwrap_or(&0),
             ),
             true,
         );
+
+        // Add trend as a small bar chart of the latest buckets
+        let recent = &stats.trend[stats.trend.len().saturating_sub(TREND_BUCKETS_SHOWN)..];
+        let max = recent.iter().map(|(_, count)| *count).max().unwrap_or(1).max(1);
+        let mut trend_text = String::new();
+        for (bucket, count) in recent {
+            let bar_len = (*count * 10 + max - 1) / max;
+            trend_text.push_str(&format!(
+                "`{}` {} {}\n",
+                bucket,
+                "█".repeat(bar_len as usize),
+                count
+            ));
+        }
+        let trend_title = if filter.monthly { "Monthly Trend" } else { "Weekly Trend (week of)" };
+        embed = embed.field(trend_title, trend_text, true);
     }
 
+    let mut pages = vec![];
+
     // Add top moderators
     if !stats.top_mods.is_empty() {
         let mut top_mods_text = String::new();
         for (i, (mod_id, count)) in stats.top_mods.iter().take(5).enumerate() {
-            let mod_name = match mod_id.parse::<u64>() {
-                Ok(id) => {
-                    match serenity::UserId::new(id).to_user(ctx.http()).await {
-                        Ok(user) => user.name,
-                        Err(_) => mod_id.clone(),
-                    }
-                }
-                Err(_) => mod_id.clone(),
-            };
-            top_mods_text.push_str(&format!("{}. **{}** - {} actions\n", i + 1, mod_name, count));
+            top_mods_text.push_str(&format!(
+                "{}. {} - {} actions\n",
+                i + 1,
+                describe_moderator(mod_id),
+                count
+            ));
+        }
+        if stats.top_mods.len() > 5 {
+            top_mods_text.push_str("*Full leaderboard on the next pages*");
+        }
+        pages.push(embed.field("Top Moderators", top_mods_text, false));
+
+        // Leaderboard pages with each moderator's breakdown
+        for (page_index, chunk) in stats.top_mods.chunks(LEADERBOARD_PER_PAGE).enumerate() {
+            let mut description = String::new();
+            for (i, (mod_id, count)) in chunk.iter().enumerate() {
+                description.push_str(&format!(
+                    "{}. {} - **{}** actions\n{}\n\n",
+                    page_index * LEADERBOARD_PER_PAGE + i + 1,
+                    describe_moderator(mod_id),
+                    count,
+                    mod_breakdowns
+                        .get(mod_id.as_str())
+                        .map(|b| b.join(" · "))
+                        .unwrap_or_default()
+                ));
+            }
+            pages.push(
+                serenity::CreateEmbed::new()
+                    .title(format!("Moderator Leaderboard for {}", guild.name))
+                    .color(0xff69b4)
+                    .description(description),
+            );
         }
-        embed = embed.field("Top Moderators", top_mods_text, false);
     } else {
-        embed = embed.field(
+        pages.push(embed.field(
             "No Tracked Actions",
             "Run `/scan-bans` to import moderation history.",
             false,
-        );
+        ));
     }
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    // Page switches replace the message's attachments, so the chart gets its own message
+    if chart.unwrap_or(false) && stats.total > 0 {
+        let png = stats_chart::render_mod_stats(&stats, |mod_id| match mod_id {
+            "0" => "Unknown".to_string(),
+            id => id
+                .parse()
+                .ok()
+                .and_then(|id| ctx.cache().user(serenity::UserId::new(id)).map(|u| u.name.clone()))
+                .unwrap_or_else(|| id.to_string()),
+        })?;
+        ctx.send(
+            poise::CreateReply::default()
+                .attachment(serenity::CreateAttachment::bytes(png, "mod-stats.png")),
+        )
+        .await?;
+    }
+
+    paginate_embeds(ctx, &pages).await?;
+
+    Ok(())
+}
+
+/// Export the server's moderation history as a file
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "mod-export",
+    required_permissions = "MANAGE_GUILD",
+    guild_only
+)]
+pub async fn mod_export(
+    ctx: Context<'_>,
+    #[description = "File format (default csv)"] format: Option<ExportFormat>,
+    #[description = "Start date (YYYY-MM-DD) or how long ago, e.g. 30d"] since: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let format = format.unwrap_or(ExportFormat::Csv);
+
+    let since = match since.as_deref().map(|s| parse_time_bound(s, false)) {
+        Some(None) => {
+            ctx.say(":x: `since` must be a date like 2025-01-31 or a duration like 30d").await?;
+            return Ok(());
+        }
+        Some(timestamp) => timestamp,
+        None => None,
+    };
+
+    ctx.defer().await?;
+
+    let actions = ctx.data().db.get_mod_actions(guild_id.get(), since).await?;
+    if actions.is_empty() {
+        ctx.say(":x: No moderation actions to export").await?;
+        return Ok(());
+    }
+
+    let mut output = Vec::new();
+    mod_export::write(&actions, format, ctx.cache(), &mut output)?;
+    if output.len() > MAX_EXPORT_BYTES {
+        ctx.say(":x: The export is too large to upload, narrow it down with `since`").await?;
+        return Ok(());
+    }
+
+    let filename = format!("mod-actions-{}.{}", guild_id, format.extension());
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(":white_check_mark: Exported {} moderation action(s)", actions.len()))
+            .attachment(serenity::CreateAttachment::bytes(output, filename)),
+    )
+    .await?;
 
     Ok(())
 }
@@ -339,41 +1709,68 @@ pub async fn scan_bans(
             // Fetch all bans
             let bans = guild_id.bans(ctx.http(), None, None).await?;
             let mut imported = 0;
+            let mut skipped = 0;
 
             for ban in bans {
+                // This ban is already known, possibly with the real moderator from the audit log.
+                // An older ban that was since lifted doesn't count.
+                if ctx.data().db.is_ban_on_record(guild_id.get(), ban.user.id.get()).await? {
+                    skipped += 1;
+                    continue;
+                }
+
                 // Add to database (we don't know who banned, so moderator is "unknown")
+                // Imports go straight to the database rather than flooding the mod-log
                 ctx.data()
                     .db
-                    .add_mod_action(
+                    .add_mod_action(&ModAction::new(
                         guild_id.get(),
                         0, // Unknown moderator
                         ban.user.id.get(),
                         "ban",
                         ban.reason.as_deref(),
-                        chrono::Utc::now().timestamp(),
-                    )
+                    ))
                     .await?;
                 imported += 1;
             }
 
             status_msg
                 .edit(ctx, poise::CreateReply::default().content(format!(
-                    ":white_check_mark: Imported {} bans from ban list.\nNote: Moderator info is not available from ban list.",
-                    imported
+                    ":white_check_mark: Imported {} bans from ban list ({} already known).\nNote: Moderator info is not available from ban list, run `/scan-bans audit` to fill it in where the audit log still has it.",
+                    imported, skipped
                 )))
                 .await?;
         }
         "audit" | "auditlog" => {
+            let (imported, skipped, complete) = match import_audit_log(ctx, guild_id).await {
+                Ok(counts) => counts,
+                Err(e) => {
+                    status_msg
+                        .edit(ctx, poise::CreateReply::default().content(format!(
+                            ":x: Couldn't read the audit log, I need the View Audit Log permission ({})",
+                            e
+                        )))
+                        .await?;
+                    return Ok(());
+                }
+            };
+
+            let note = if complete {
+                "Next scan continues from where this one stopped."
+            } else {
+                "There's more left in the audit log, run the scan again to continue."
+            };
             status_msg
-                .edit(ctx, poise::CreateReply::default().content(
-                    ":information_source: Audit log scanning requires additional API calls. Use `/scan-bans bans` for basic import.",
-                ))
+                .edit(ctx, poise::CreateReply::default().content(format!(
+                    ":white_check_mark: Imported {} actions from the audit log ({} already known).\n{}",
+                    imported, skipped, note
+                )))
                 .await?;
         }
         _ => {
             status_msg
                 .edit(ctx, poise::CreateReply::default().content(
-                    "Usage: `/scan-bans [bans|audit]`\n- `bans`: Import from ban list\n- `audit`: Import from audit logs (limited history)",
+                    "Usage: `/scan-bans [bans|audit]`\n- `bans`: Import from ban list\n- `audit`: Import bans, kicks, unbans and timeouts from audit logs (limited history)",
                 ))
                 .await?;
         }
@@ -381,3 +1778,214 @@ pub async fn scan_bans(
 
     Ok(())
 }
+
+/// Fetch audit log entries of one type newer than `cursor`, oldest first.
+/// Also returns whether paging reached the end of the log rather than the page cap.
+async fn fetch_audit_entries(
+    ctx: Context<'_>,
+    guild_id: serenity::GuildId,
+    action: serenity::audit_log::Action,
+    cursor: Option<u64>,
+) -> Result<(Vec<serenity::AuditLogEntry>, bool), Error> {
+    let mut entries = Vec::new();
+    let mut after = cursor.unwrap_or(0);
+
+    for _ in 0..AUDIT_IMPORT_MAX_PAGES {
+        // serenity only pages audit logs backwards, `after` walks forwards from the cursor
+        let request = serenity::Request::new(serenity::Route::GuildAuditLogs { guild_id }, serenity::LightMethod::Get)
+            .params(Some(vec![
+                ("action_type", action.num().to_string()),
+                ("after", after.to_string()),
+                ("limit", "100".to_string()),
+            ]));
+        let mut page: serenity::AuditLogs = ctx.http().fire(request).await?;
+        page.entries.sort_by_key(|entry| entry.id);
+
+        let done = page.entries.len() < 100;
+        if let Some(last) = page.entries.last() {
+            after = last.id.get();
+        }
+        entries.extend(page.entries);
+
+        if done {
+            return Ok((entries, true));
+        }
+    }
+
+    Ok((entries, false))
+}
+
+/// Turn an audit log entry into the moderation action it represents, if any
+fn audit_entry_action(guild_id: u64, entry: &serenity::AuditLogEntry) -> Option<ModAction> {
+    use serenity::audit_log::{Action, Change, MemberAction};
+
+    let target_id = entry.target_id?.get();
+    let timestamp = entry.id.created_at().unix_timestamp();
+
+    let (action, duration) = match entry.action {
+        Action::Member(MemberAction::BanAdd) => ("ban", None),
+        Action::Member(MemberAction::BanRemove) => ("unban", None),
+        Action::Member(MemberAction::Kick) => ("kick", None),
+        Action::Member(MemberAction::Update) => {
+            // Only member updates that start a timeout
+            let until = entry.changes.as_ref()?.iter().find_map(|change| match change {
+                Change::CommunicationDisabledUntil { new: Some(until), .. } => Some(until.unix_timestamp()),
+                _ => None,
+            })?;
+            if until <= timestamp {
+                return None;
+            }
+            ("timeout", Some(until - timestamp))
+        }
+        _ => return None,
+    };
+
+    Some(ModAction {
+        duration,
+        timestamp,
+        audit_entry_id: Some(entry.id.get()),
+        ..ModAction::new(guild_id, entry.user_id.get(), target_id, action, entry.reason.as_deref())
+    })
+}
+
+/// Import moderation actions from the audit log, oldest first, picking up after the last imported entry.
+/// Returns how many actions were imported, how many were already known, and whether the scan
+/// caught up with the log.
+async fn import_audit_log(ctx: Context<'_>, guild_id: serenity::GuildId) -> Result<(usize, usize, bool), Error> {
+    use serenity::audit_log::{Action, MemberAction};
+
+    let db = &ctx.data().db;
+    let cursor = db.get_audit_import_cursor(guild_id.get()).await?;
+
+    let mut entries = Vec::new();
+    // Where the next scan has to start so that types cut off by the page cap resume without gaps
+    let mut resume_at: Option<u64> = None;
+    for action in [
+        MemberAction::BanAdd,
+        MemberAction::BanRemove,
+        MemberAction::Kick,
+        MemberAction::Update,
+    ] {
+        let (fetched, complete) = fetch_audit_entries(ctx, guild_id, Action::Member(action), cursor).await?;
+        if !complete {
+            let last = fetched.last().map(|entry| entry.id.get()).unwrap_or_default();
+            resume_at = Some(resume_at.map_or(last, |resume| resume.min(last)));
+        }
+        entries.extend(fetched);
+    }
+    entries.sort_by_key(|entry| entry.id);
+
+    let bot_user_id = ctx.cache().current_user().id;
+    let mut imported = 0;
+    let mut skipped = 0;
+
+    for entry in &entries {
+        // Actions taken through the bot are recorded when they happen
+        if entry.user_id == bot_user_id {
+            continue;
+        }
+        if let Some(action) = audit_entry_action(guild_id.get(), entry) {
+            if db.import_mod_action(&action).await? {
+                imported += 1;
+            } else {
+                skipped += 1;
+            }
+        }
+    }
+
+    // Entries past the resume point are read again next time and skipped as already known
+    let new_cursor = resume_at.or_else(|| entries.last().map(|entry| entry.id.get()));
+    if let Some(new_cursor) = new_cursor.filter(|c| Some(*c) > cursor) {
+        db.set_audit_import_cursor(guild_id.get(), new_cursor).await?;
+    }
+
+    Ok((imported, skipped, resume_at.is_none()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_user_ids_reads_ids_and_mentions() {
+        assert_eq!(
+            parse_user_ids("123456789012345678 <@234567890123456789>, <@!345678901234567890>"),
+            vec![123456789012345678, 234567890123456789, 345678901234567890]
+        );
+        assert_eq!(parse_user_ids("12345678901234567\n98765432109876543"), vec![12345678901234567, 98765432109876543]);
+    }
+
+    #[test]
+    fn parse_user_ids_dedupes() {
+        assert_eq!(
+            parse_user_ids("123456789012345678 <@123456789012345678> 123456789012345678"),
+            vec![123456789012345678]
+        );
+    }
+
+    #[test]
+    fn parse_user_ids_skips_bad_lengths() {
+        // 16 and 21 digits, and a 20 digit number that doesn't fit in a u64
+        assert!(parse_user_ids("1234567890123456 123456789012345678901 99999999999999999999").is_empty());
+        assert!(parse_user_ids("").is_empty());
+        assert!(parse_user_ids("not an id").is_empty());
+    }
+
+    // 2024-01-01 00:00:00 UTC
+    const ENTRY_TIME: i64 = 1_704_067_200;
+
+    fn audit_entry(action_type: u8, changes: serde_json::Value) -> serenity::AuditLogEntry {
+        let id = ((ENTRY_TIME * 1000 - 1_420_070_400_000) as u64) << 22;
+        serde_json::from_value(serde_json::json!({
+            "target_id": "222222222222222222",
+            "action_type": action_type,
+            "reason": "spamming",
+            "user_id": "111111111111111111",
+            "changes": changes,
+            "id": id.to_string(),
+            "options": null,
+        }))
+        .unwrap()
+    }
+
+    fn entry_action(action_type: u8, changes: serde_json::Value) -> Option<(String, Option<i64>)> {
+        let action = audit_entry_action(1, &audit_entry(action_type, changes))?;
+        assert_eq!(action.guild_id, 1);
+        assert_eq!(action.moderator_id, 111111111111111111);
+        assert_eq!(action.target_id, 222222222222222222);
+        assert_eq!(action.reason.as_deref(), Some("spamming"));
+        assert_eq!(action.timestamp, ENTRY_TIME);
+        assert!(action.audit_entry_id.is_some());
+        Some((action.action, action.duration))
+    }
+
+    #[test]
+    fn audit_entry_action_maps_bans_and_kicks() {
+        assert_eq!(entry_action(22, serde_json::Value::Null), Some(("ban".to_string(), None)));
+        assert_eq!(entry_action(23, serde_json::Value::Null), Some(("unban".to_string(), None)));
+        assert_eq!(entry_action(20, serde_json::Value::Null), Some(("kick".to_string(), None)));
+    }
+
+    #[test]
+    fn audit_entry_action_maps_timeouts() {
+        let timeout = serde_json::json!([{
+            "key": "communication_disabled_until",
+            "new_value": "2024-01-01T01:00:00Z",
+        }]);
+        assert_eq!(entry_action(24, timeout), Some(("timeout".to_string(), Some(3600))));
+    }
+
+    #[test]
+    fn audit_entry_action_skips_other_member_updates() {
+        let nick = serde_json::json!([{ "key": "nick", "old_value": "old", "new_value": "new" }]);
+        assert_eq!(entry_action(24, nick), None);
+        let removed = serde_json::json!([{
+            "key": "communication_disabled_until",
+            "old_value": "2024-01-01T01:00:00Z",
+        }]);
+        assert_eq!(entry_action(24, removed), None);
+        assert_eq!(entry_action(24, serde_json::Value::Null), None);
+        // Channel creation isn't a moderation action
+        assert_eq!(entry_action(10, serde_json::Value::Null), None);
+    }
+}
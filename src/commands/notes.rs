@@ -0,0 +1,137 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::database::UserNote;
+use crate::utils::{paginate_embeds, truncate};
+use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+const NOTES_PER_PAGE: usize = 10;
+const MAX_NOTE_LENGTH: usize = 1000;
+
+/// Private moderator notes about users
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    subcommands("note_add", "note_list", "note_remove")
+)]
+pub async fn note(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Add a note about a user
+#[poise::command(slash_command, prefix_command, rename = "add")]
+pub async fn note_add(
+    ctx: Context<'_>,
+    #[description = "User the note is about"] user: serenity::User,
+    #[description = "Note text"]
+    #[rest]
+    content: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    if content.chars().count() > MAX_NOTE_LENGTH {
+        ctx.say(format!(":x: Notes can be at most {} characters", MAX_NOTE_LENGTH))
+            .await?;
+        return Ok(());
+    }
+
+    let id = ctx
+        .data()
+        .db
+        .add_user_note(&UserNote {
+            id: 0,
+            guild_id: guild_id.get(),
+            user_id: user.id.get(),
+            author_id: ctx.author().id.get(),
+            content,
+            timestamp: chrono::Utc::now().timestamp(),
+        })
+        .await?;
+
+    ctx.say(format!(":white_check_mark: Added note {} for **{}**", id, user.name))
+        .await?;
+
+    Ok(())
+}
+
+/// Show the notes about a user
+#[poise::command(slash_command, prefix_command, rename = "list")]
+pub async fn note_list(
+    ctx: Context<'_>,
+    #[description = "User to show notes for"] user: serenity::User,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let notes = ctx.data().db.get_user_notes(guild_id.get(), user.id.get()).await?;
+
+    if notes.is_empty() {
+        ctx.say(format!("**{}** has no notes.", user.name)).await?;
+        return Ok(());
+    }
+
+    let pages: Vec<_> = notes
+        .chunks(NOTES_PER_PAGE)
+        .map(|chunk| {
+            let mut description = String::new();
+            for note in chunk {
+                description.push_str(&format!(
+                    "**ID {}** - <t:{}:f> by <@{}>\n{}\n\n",
+                    note.id,
+                    note.timestamp,
+                    note.author_id,
+                    truncate(&note.content, 300)
+                ));
+            }
+
+            serenity::CreateEmbed::new()
+                .title(format!("Notes for {} ({})", user.name, notes.len()))
+                .thumbnail(user.face())
+                .color(0xff69b4)
+                .description(description)
+        })
+        .collect();
+
+    paginate_embeds(ctx, &pages).await?;
+
+    Ok(())
+}
+
+/// Delete a note by ID
+#[poise::command(slash_command, prefix_command, rename = "remove")]
+pub async fn note_remove(
+    ctx: Context<'_>,
+    #[description = "Note ID"] id: i64,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    match ctx.data().db.remove_user_note(guild_id.get(), id).await? {
+        Some(user_id) => {
+            ctx.say(format!(":white_check_mark: Deleted note {} for <@{}>", id, user_id))
+                .await?;
+        }
+        None => {
+            ctx.say(format!(":x: No note with ID {} in this server", id)).await?;
+        }
+    }
+
+    Ok(())
+}
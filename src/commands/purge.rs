@@ -0,0 +1,382 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::utils::is_url;
+use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
+use regex::{Regex, RegexBuilder};
+
+const DEFAULT_PURGE_COUNT: u16 = 100;
+const MAX_PURGE_COUNT: u16 = 1000;
+// How far back to look for matching messages
+const MAX_PURGE_SCAN: usize = 5000;
+// Bulk delete refuses messages older than 14 days, leave a little margin
+const BULK_DELETE_MAX_AGE_SECS: i64 = 14 * 86400 - 60;
+
+/// Which messages a purge deletes. A message has to pass every filter that's set,
+/// `Some(false)` on a yes/no filter keeps only the messages without it.
+#[derive(Debug, Default)]
+struct PurgeFilter {
+    user: Option<serenity::UserId>,
+    bots: Option<bool>,
+    contains: Option<String>,
+    regex: Option<Regex>,
+    attachments: Option<bool>,
+    links: Option<bool>,
+    embeds: Option<bool>,
+    before: Option<serenity::MessageId>,
+    after: Option<serenity::MessageId>,
+}
+
+impl PurgeFilter {
+    /// Parse the filter words of a purge, e.g. `bots links contains:"free nitro" before:123`
+    fn parse(user: Option<serenity::UserId>, input: &str) -> Result<Self, String> {
+        let mut filter = PurgeFilter {
+            user,
+            ..Default::default()
+        };
+
+        for token in split_quoted(input)? {
+            let (key, value) = token.split_once(':').unwrap_or((&token, ""));
+            let value = |what: &str| {
+                (!value.is_empty())
+                    .then(|| value.to_string())
+                    .ok_or_else(|| format!("`{}:` needs {}", key, what))
+            };
+            let message_id = |what: &str| {
+                parse_message_id(&value(what)?).ok_or_else(|| format!("`{}:` must be a message ID or link", key))
+            };
+
+            match key.to_lowercase().as_str() {
+                "bots" => filter.bots = Some(true),
+                "humans" => filter.bots = Some(false),
+                "attachments" => filter.attachments = Some(true),
+                "no-attachments" => filter.attachments = Some(false),
+                "links" => filter.links = Some(true),
+                "no-links" => filter.links = Some(false),
+                "embeds" => filter.embeds = Some(true),
+                "no-embeds" => filter.embeds = Some(false),
+                "contains" => filter.contains = Some(value("some text")?.to_lowercase()),
+                "regex" => {
+                    let regex = RegexBuilder::new(&value("a pattern")?)
+                        .size_limit(1 << 20)
+                        .build()
+                        .map_err(|e| format!("Invalid regex: {}", e))?;
+                    filter.regex = Some(regex);
+                }
+                "before" => filter.before = Some(message_id("a message ID or link")?),
+                "after" => filter.after = Some(message_id("a message ID or link")?),
+                _ => {
+                    return Err(format!(
+                        "Unknown filter `{}`. Use `contains:`, `regex:`, `before:`, `after:`, `bots`, `humans`, \
+                         `attachments`, `links` or `embeds` (the last three also as `no-attachments` and so on)",
+                        token
+                    ))
+                }
+            }
+        }
+
+        Ok(filter)
+    }
+
+    fn matches(&self, msg: &serenity::Message) -> bool {
+        let has_links = || {
+            msg.content
+                .split_whitespace()
+                .any(|word| is_url(word.trim_start_matches(['<', '(', '['])))
+        };
+
+        self.user.is_none_or(|user_id| msg.author.id == user_id)
+            && self.bots.is_none_or(|bots| msg.author.bot == bots)
+            && self
+                .contains
+                .as_ref()
+                .is_none_or(|text| msg.content.to_lowercase().contains(text))
+            && self.regex.as_ref().is_none_or(|regex| regex.is_match(&msg.content))
+            && self
+                .attachments
+                .is_none_or(|attachments| msg.attachments.is_empty() != attachments)
+            && self.links.is_none_or(|links| has_links() == links)
+            && self.embeds.is_none_or(|embeds| msg.embeds.is_empty() != embeds)
+            && self.before.is_none_or(|before| msg.id < before)
+            && self.after.is_none_or(|after| msg.id > after)
+    }
+}
+
+/// Delete messages, optionally only those matching every filter given
+///
+/// Filters are words after the count and user, e.g.
+/// `purge 50 @someone links contains:"free nitro"`. They are `contains:`,
+/// `regex:`, `before:`, `after:` and `bots`, `humans`, `attachments`,
+/// `links` and `embeds`, the last three also negated as `no-attachments` and so on.
+#[poise::command(slash_command, prefix_command, required_permissions = "MANAGE_MESSAGES", guild_only)]
+pub async fn purge(
+    ctx: Context<'_>,
+    #[description = "Number of messages to delete (default 100, max 1000)"] count: Option<u16>,
+    #[description = "Only messages from this user"] user: Option<serenity::User>,
+    #[description = "Filters, e.g. bots links no-embeds contains:\"free nitro\" before:<message ID>"]
+    #[rest]
+    filters: Option<String>,
+) -> Result<(), Error> {
+    let filter = match PurgeFilter::parse(user.map(|u| u.id), filters.as_deref().unwrap_or("")) {
+        Ok(filter) => filter,
+        Err(e) => {
+            ctx.say(format!(":x: {}", e)).await?;
+            return Ok(());
+        }
+    };
+    let count = count.unwrap_or(DEFAULT_PURGE_COUNT).clamp(1, MAX_PURGE_COUNT) as usize;
+
+    // Keep the reply out of the channel history we're about to go through
+    ctx.defer_ephemeral().await?;
+
+    let channel = ctx.channel_id();
+    let bulk_cutoff = chrono::Utc::now().timestamp() - BULK_DELETE_MAX_AGE_SECS;
+
+    // Walk back through the channel history, 100 messages at a time
+    let mut recent = Vec::new();
+    let mut old = Vec::new();
+    let mut scanned = 0;
+    let mut cursor = filter.before;
+
+    'scan: while recent.len() + old.len() < count && scanned < MAX_PURGE_SCAN {
+        let mut request = serenity::GetMessages::new().limit(100);
+        if let Some(cursor) = cursor {
+            request = request.before(cursor);
+        }
+        let messages = channel.messages(ctx.http(), request).await?;
+        if messages.is_empty() {
+            break;
+        }
+
+        for msg in &messages {
+            scanned += 1;
+            if filter.after.is_some_and(|after| msg.id <= after) {
+                break 'scan;
+            }
+            if !filter.matches(msg) {
+                continue;
+            }
+
+            if msg.id.created_at().unix_timestamp() > bulk_cutoff {
+                recent.push(msg.id);
+            } else {
+                old.push(msg.id);
+            }
+            if recent.len() + old.len() >= count {
+                break 'scan;
+            }
+        }
+
+        cursor = messages.last().map(|m| m.id);
+    }
+
+    for chunk in recent.chunks(100) {
+        if chunk.len() > 1 {
+            channel.delete_messages(ctx.http(), chunk).await?;
+        } else {
+            channel.delete_message(ctx.http(), chunk[0]).await?;
+        }
+    }
+
+    // Bulk delete refuses these, so they go one by one
+    let mut old_deleted = 0;
+    for message_id in &old {
+        match channel.delete_message(ctx.http(), *message_id).await {
+            Ok(()) => old_deleted += 1,
+            Err(e) => tracing::warn!("Failed to delete old message {}: {}", message_id, e),
+        }
+    }
+
+    let mut response = format!(
+        ":white_check_mark: Deleted {} message(s) after scanning {}",
+        recent.len() + old_deleted,
+        scanned
+    );
+    if !old.is_empty() {
+        response.push_str(&format!(
+            "\n{} of them were older than 14 days and deleted one by one",
+            old_deleted
+        ));
+    }
+    if old_deleted < old.len() {
+        response.push_str(&format!("\n:x: {} old message(s) couldn't be deleted", old.len() - old_deleted));
+    }
+
+    let reply = ctx
+        .send(poise::CreateReply::default().content(response).ephemeral(true))
+        .await?;
+
+    // Prefix replies can't be ephemeral, clean them up like `clean` does
+    if let poise::Context::Prefix(_) = ctx {
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        reply.delete(ctx).await?;
+    }
+
+    Ok(())
+}
+
+/// Split filter words on whitespace, keeping "quoted text" together
+fn split_quoted(input: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut in_token = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_token = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut token));
+                    in_token = false;
+                }
+            }
+            c => {
+                token.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if quoted {
+        return Err("Unclosed quote".to_string());
+    }
+    if in_token {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Parse a message ID, or the ID at the end of a message link
+fn parse_message_id(input: &str) -> Option<serenity::MessageId> {
+    input
+        .trim()
+        .rsplit('/')
+        .next()
+        .and_then(|id| id.parse::<u64>().ok())
+        .filter(|id| *id != 0)
+        .map(serenity::MessageId::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(author_id: u64, bot: bool, content: &str, attachments: usize) -> serenity::Message {
+        let attachments: Vec<_> = (0..attachments)
+            .map(|i| {
+                serde_json::json!({
+                    "id": (i + 1).to_string(),
+                    "filename": "cat.png",
+                    "size": 1024,
+                    "url": "https://cdn.discordapp.com/cat.png",
+                    "proxy_url": "https://media.discordapp.net/cat.png",
+                })
+            })
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "id": "500",
+            "channel_id": "2",
+            "author": {
+                "id": author_id.to_string(),
+                "username": "someone",
+                "discriminator": "0",
+                "avatar": null,
+                "bot": bot,
+            },
+            "content": content,
+            "timestamp": "2024-01-01T00:00:00Z",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": attachments,
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_filter_words() {
+        let filter = PurgeFilter::parse(
+            Some(serenity::UserId::new(123)),
+            r#"bots links no-embeds contains:"Free Nitro" before:456 after:https://discord.com/channels/1/2/100"#,
+        )
+        .unwrap();
+        assert_eq!(filter.user, Some(serenity::UserId::new(123)));
+        assert_eq!(filter.bots, Some(true));
+        assert_eq!(filter.links, Some(true));
+        assert_eq!(filter.embeds, Some(false));
+        assert_eq!(filter.attachments, None);
+        assert_eq!(filter.contains.as_deref(), Some("free nitro"));
+        assert_eq!(filter.before, Some(serenity::MessageId::new(456)));
+        assert_eq!(filter.after, Some(serenity::MessageId::new(100)));
+
+        assert_eq!(PurgeFilter::parse(None, "humans").unwrap().bots, Some(false));
+        assert!(PurgeFilter::parse(None, r#"regex:"^a b$""#).unwrap().regex.unwrap().is_match("a b"));
+        assert!(PurgeFilter::parse(None, "").unwrap().matches(&message(1, false, "anything", 0)));
+    }
+
+    #[test]
+    fn parse_filter_errors() {
+        assert!(PurgeFilter::parse(None, "stickers").is_err());
+        assert!(PurgeFilter::parse(None, "contains:").is_err());
+        assert!(PurgeFilter::parse(None, "before:yesterday").is_err());
+        assert!(PurgeFilter::parse(None, "regex:(").is_err());
+        assert!(PurgeFilter::parse(None, r#"contains:"open"#).is_err());
+    }
+
+    #[test]
+    fn filters_combine_with_and() {
+        let filter = PurgeFilter::parse(Some(serenity::UserId::new(1)), "bots attachments contains:nitro").unwrap();
+
+        assert!(filter.matches(&message(1, true, "Free NITRO", 1)));
+        // Each filter on its own rules the message out
+        assert!(!filter.matches(&message(2, true, "Free NITRO", 1)));
+        assert!(!filter.matches(&message(1, false, "Free NITRO", 1)));
+        assert!(!filter.matches(&message(1, true, "hello", 1)));
+        assert!(!filter.matches(&message(1, true, "Free NITRO", 0)));
+    }
+
+    #[test]
+    fn negated_filters_keep_messages_without() {
+        let filter = PurgeFilter::parse(None, "humans no-attachments no-links").unwrap();
+
+        assert!(filter.matches(&message(1, false, "just chatting", 0)));
+        assert!(!filter.matches(&message(1, true, "just chatting", 0)));
+        assert!(!filter.matches(&message(1, false, "just chatting", 2)));
+        assert!(!filter.matches(&message(1, false, "see (https://example.com)", 0)));
+    }
+
+    #[test]
+    fn message_bounds() {
+        let filter = PurgeFilter::parse(None, "after:400 before:600").unwrap();
+        assert!(filter.matches(&message(1, false, "", 0)));
+        let filter = PurgeFilter::parse(None, "after:500").unwrap();
+        assert!(!filter.matches(&message(1, false, "", 0)));
+    }
+}
@@ -0,0 +1,690 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::database::{LinkDomain, SpamExemption, SpamFilterSettings, SpamRuleConfig};
+use crate::modules::spam_filter::{self, SpamAction, SpamRule};
+use crate::utils::{format_duration, HumanDuration, MAX_TIMEOUT_SECS};
+use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
+use poise::ChoiceParameter;
+
+/// Manage the spam filter for this server
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "spam-filter",
+    required_permissions = "MANAGE_GUILD",
+    guild_only,
+    subcommands(
+        "spam_filter_enable",
+        "spam_filter_disable",
+        "spam_filter_rule",
+        "spam_filter_reset",
+        "spam_filter_status",
+        "spam_filter_observe",
+        "spam_filter_limit",
+        "spam_filter_links",
+        "spam_filter_invites",
+        "spam_filter_exempt"
+    )
+)]
+pub async fn spam_filter(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Turn the spam filter on for this server
+#[poise::command(slash_command, prefix_command, rename = "enable")]
+pub async fn spam_filter_enable(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    ctx.data().db.set_spam_filter_enabled(guild_id.get(), true).await?;
+    spam_filter::invalidate_config(guild_id.get()).await;
+
+    ctx.say(":white_check_mark: Spam filter enabled").await?;
+    Ok(())
+}
+
+/// Turn the spam filter off for this server
+#[poise::command(slash_command, prefix_command, rename = "disable")]
+pub async fn spam_filter_disable(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    ctx.data().db.set_spam_filter_enabled(guild_id.get(), false).await?;
+    spam_filter::invalidate_config(guild_id.get()).await;
+
+    ctx.say(":white_check_mark: Spam filter disabled").await?;
+    Ok(())
+}
+
+/// Configure a spam filter rule
+#[poise::command(slash_command, prefix_command, rename = "rule")]
+pub async fn spam_filter_rule(
+    ctx: Context<'_>,
+    #[description = "Rule to configure"] rule: SpamRule,
+    #[description = "Whether the rule is checked"] enabled: Option<bool>,
+    #[description = "Action taken once the threshold is reached"] action: Option<SpamAction>,
+    #[description = "Warnings before the action is taken"]
+    #[min = 1]
+    #[max = 20]
+    threshold: Option<u32>,
+    #[description = "Timeout length, e.g. 10m or 1h (timeout action only)"] timeout: Option<HumanDuration>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    if let Some(timeout) = timeout {
+        if !(60..=MAX_TIMEOUT_SECS).contains(&timeout.as_secs()) {
+            ctx.say(":x: Timeout must be between 1 minute and 28 days").await?;
+            return Ok(());
+        }
+    }
+
+    let rules = spam_filter::load_rules(ctx.data(), guild_id.get()).await?;
+    let mut settings = rules[&rule];
+
+    if let Some(enabled) = enabled {
+        settings.enabled = enabled;
+    }
+    if let Some(action) = action {
+        settings.action = action;
+    }
+    if let Some(threshold) = threshold {
+        settings.threshold = threshold;
+    }
+    if let Some(timeout) = timeout {
+        settings.timeout_minutes = timeout.as_minutes();
+    }
+
+    ctx.data()
+        .db
+        .set_spam_rule(&SpamRuleConfig {
+            guild_id: guild_id.get(),
+            rule: rule.name().to_string(),
+            enabled: settings.enabled,
+            action: settings.action.name().to_string(),
+            threshold: settings.threshold as i64,
+            timeout_minutes: settings.timeout_minutes,
+        })
+        .await?;
+    spam_filter::invalidate_config(guild_id.get()).await;
+
+    ctx.say(format!(
+        ":white_check_mark: Rule `{}` updated: {}",
+        rule.name(),
+        describe_settings(&settings)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Reset a spam filter rule to its defaults
+#[poise::command(slash_command, prefix_command, rename = "reset")]
+pub async fn spam_filter_reset(
+    ctx: Context<'_>,
+    #[description = "Rule to reset"] rule: SpamRule,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    ctx.data().db.delete_spam_rule(guild_id.get(), rule.name()).await?;
+    spam_filter::invalidate_config(guild_id.get()).await;
+
+    ctx.say(format!(":white_check_mark: Rule `{}` reset to defaults", rule.name()))
+        .await?;
+
+    Ok(())
+}
+
+/// Toggle observe mode, where violations are only reported instead of acted on
+#[poise::command(slash_command, prefix_command, rename = "observe")]
+pub async fn spam_filter_observe(
+    ctx: Context<'_>,
+    #[description = "Whether observe mode is on"] enabled: bool,
+    #[description = "Channel that receives reports"] report_channel: Option<serenity::GuildChannel>,
+    #[description = "Stop posting reports to a channel"] clear_report_channel: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let clear_report_channel = clear_report_channel.unwrap_or(false);
+
+    if clear_report_channel && report_channel.is_some() {
+        ctx.say(":x: Either set a report channel or clear it, not both").await?;
+        return Ok(());
+    }
+
+    let mut settings = ctx.data().db.get_spam_filter_settings(guild_id.get()).await?;
+    settings.observe_mode = enabled;
+    if let Some(channel) = &report_channel {
+        settings.report_channel_id = Some(channel.id.get());
+    } else if clear_report_channel {
+        settings.report_channel_id = None;
+    }
+    ctx.data().db.set_spam_filter_settings(&settings).await?;
+    spam_filter::invalidate_config(guild_id.get()).await;
+
+    let message = match (enabled, settings.report_channel_id) {
+        (false, _) => ":white_check_mark: Observe mode disabled. Violations will be acted on.".to_string(),
+        (true, Some(channel_id)) => format!(
+            ":white_check_mark: Observe mode enabled. Reports will be posted to <#{}>.",
+            channel_id
+        ),
+        (true, None) => ":white_check_mark: Observe mode enabled. Reports will only be written to the activity log until you set a report channel."
+            .to_string(),
+    };
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// A tunable limit used by the flood, duplicate and content rules
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum SpamLimit {
+    #[name = "flood_messages"]
+    FloodMessages,
+    #[name = "flood_seconds"]
+    FloodSeconds,
+    #[name = "duplicate_count"]
+    DuplicateCount,
+    #[name = "duplicate_seconds"]
+    DuplicateSeconds,
+    #[name = "duplicate_similarity"]
+    DuplicateSimilarity,
+    #[name = "mention_limit"]
+    MentionLimit,
+    #[name = "caps_percent"]
+    CapsPercent,
+    #[name = "caps_min_length"]
+    CapsMinLength,
+    #[name = "zalgo_percent"]
+    ZalgoPercent,
+    #[name = "emoji_limit"]
+    EmojiLimit,
+    #[name = "newline_limit"]
+    NewlineLimit,
+}
+
+impl SpamLimit {
+    fn range(self) -> std::ops::RangeInclusive<i64> {
+        match self {
+            SpamLimit::FloodMessages => 2..=50,
+            SpamLimit::FloodSeconds => 1..=60,
+            SpamLimit::DuplicateCount => 2..=20,
+            SpamLimit::DuplicateSeconds => 1..=3600,
+            SpamLimit::DuplicateSimilarity | SpamLimit::CapsPercent => 50..=100,
+            SpamLimit::ZalgoPercent => 5..=100,
+            SpamLimit::MentionLimit => 2..=100,
+            SpamLimit::CapsMinLength | SpamLimit::EmojiLimit | SpamLimit::NewlineLimit => 1..=200,
+        }
+    }
+
+    fn field(self, settings: &mut SpamFilterSettings) -> &mut i64 {
+        match self {
+            SpamLimit::FloodMessages => &mut settings.flood_messages,
+            SpamLimit::FloodSeconds => &mut settings.flood_seconds,
+            SpamLimit::DuplicateCount => &mut settings.duplicate_count,
+            SpamLimit::DuplicateSeconds => &mut settings.duplicate_seconds,
+            SpamLimit::DuplicateSimilarity => &mut settings.duplicate_similarity,
+            SpamLimit::MentionLimit => &mut settings.mention_limit,
+            SpamLimit::CapsPercent => &mut settings.caps_percent,
+            SpamLimit::CapsMinLength => &mut settings.caps_min_length,
+            SpamLimit::ZalgoPercent => &mut settings.zalgo_percent,
+            SpamLimit::EmojiLimit => &mut settings.emoji_limit,
+            SpamLimit::NewlineLimit => &mut settings.newline_limit,
+        }
+    }
+}
+
+/// Tune a limit used by the flood, duplicate and content rules
+#[poise::command(slash_command, prefix_command, rename = "limit")]
+pub async fn spam_filter_limit(
+    ctx: Context<'_>,
+    #[description = "Limit to change"] limit: SpamLimit,
+    #[description = "New value"] value: i64,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let range = limit.range();
+    if !range.contains(&value) {
+        ctx.say(format!(
+            ":x: `{}` must be between {} and {}",
+            limit.name(),
+            range.start(),
+            range.end()
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let mut settings = ctx.data().db.get_spam_filter_settings(guild_id.get()).await?;
+    *limit.field(&mut settings) = value;
+    ctx.data().db.set_spam_filter_settings(&settings).await?;
+    spam_filter::invalidate_config(guild_id.get()).await;
+
+    ctx.say(format!(":white_check_mark: Limits updated\n{}", describe_limits(&settings)))
+        .await?;
+
+    Ok(())
+}
+
+/// Show the spam filter configuration
+#[poise::command(slash_command, prefix_command, rename = "status")]
+pub async fn spam_filter_status(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let enabled = ctx.data().db.is_spam_filter_enabled(guild_id.get()).await?;
+    let rules = spam_filter::load_rules(ctx.data(), guild_id.get()).await?;
+    let settings = ctx.data().db.get_spam_filter_settings(guild_id.get()).await?;
+
+    let mut rules_text = String::new();
+    for rule in SpamRule::ALL {
+        rules_text.push_str(&format!(
+            "**{}** ({}) - {}\n",
+            rule.name(),
+            rule.description(),
+            describe_settings(&rules[&rule])
+        ));
+    }
+
+    let status = match (enabled, settings.observe_mode) {
+        (false, _) => "Disabled".to_string(),
+        (true, false) => "Enabled".to_string(),
+        (true, true) => match settings.report_channel_id {
+            Some(channel_id) => format!("Observe mode (reports in <#{}>)", channel_id),
+            None => "Observe mode (no report channel)".to_string(),
+        },
+    };
+
+    let embed = serenity::CreateEmbed::new()
+        .title("Spam Filter")
+        .color(0xff69b4)
+        .field("Status", status, false)
+        .field("Rules", rules_text, false)
+        .field("Limits", describe_limits(&settings), false);
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Manage the link allowlist and blocklist
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "links",
+    subcommands("links_allow", "links_block", "links_remove", "links_list")
+)]
+pub async fn spam_filter_links(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Allow links to a domain (use *.domain for all subdomains)
+#[poise::command(slash_command, prefix_command, rename = "allow")]
+pub async fn links_allow(
+    ctx: Context<'_>,
+    #[description = "Domain to allow, e.g. youtube.com or *.github.io"] domain: String,
+) -> Result<(), Error> {
+    set_link_domain(ctx, &domain, false).await
+}
+
+/// Block links to a domain (use *.domain for all subdomains)
+#[poise::command(slash_command, prefix_command, rename = "block")]
+pub async fn links_block(
+    ctx: Context<'_>,
+    #[description = "Domain to block, e.g. example.com or *.example.com"] domain: String,
+) -> Result<(), Error> {
+    set_link_domain(ctx, &domain, true).await
+}
+
+async fn set_link_domain(ctx: Context<'_>, domain: &str, blocked: bool) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let domain = match spam_filter::normalize_domain_pattern(domain) {
+        Some(d) => d,
+        None => {
+            ctx.say(":x: That doesn't look like a valid domain").await?;
+            return Ok(());
+        }
+    };
+
+    ctx.data()
+        .db
+        .set_link_domain(&LinkDomain {
+            guild_id: guild_id.get(),
+            domain: domain.clone(),
+            blocked,
+        })
+        .await?;
+    spam_filter::invalidate_config(guild_id.get()).await;
+
+    ctx.say(format!(
+        ":white_check_mark: `{}` added to the {}",
+        domain,
+        if blocked { "blocklist" } else { "allowlist" }
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a domain from the allowlist or blocklist
+#[poise::command(slash_command, prefix_command, rename = "remove")]
+pub async fn links_remove(
+    ctx: Context<'_>,
+    #[description = "Domain to remove"] domain: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let domain = spam_filter::normalize_domain_pattern(&domain).unwrap_or(domain);
+
+    if ctx.data().db.remove_link_domain(guild_id.get(), &domain).await? {
+        spam_filter::invalidate_config(guild_id.get()).await;
+        ctx.say(format!(":white_check_mark: `{}` removed", domain)).await?;
+    } else {
+        ctx.say(format!(":x: `{}` isn't on either list", domain)).await?;
+    }
+
+    Ok(())
+}
+
+/// List allowed and blocked domains
+#[poise::command(slash_command, prefix_command, rename = "list")]
+pub async fn links_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let domains = ctx.data().db.get_link_domains(guild_id.get()).await?;
+    let format_list = |blocked: bool| {
+        let list: Vec<_> = domains
+            .iter()
+            .filter(|d| d.blocked == blocked)
+            .map(|d| format!("`{}`", d.domain))
+            .collect();
+        if list.is_empty() {
+            "None".to_string()
+        } else {
+            list.join(", ")
+        }
+    };
+
+    let embed = serenity::CreateEmbed::new()
+        .title("Spam Filter Links")
+        .color(0xff69b4)
+        .field("Allowed", format_list(false), false)
+        .field("Blocked", format_list(true), false);
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Manage partner servers whose invites are allowed
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "invites",
+    subcommands("invites_allow", "invites_remove", "invites_list")
+)]
+pub async fn spam_filter_invites(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Allow invites to a partner server
+#[poise::command(slash_command, prefix_command, rename = "allow")]
+pub async fn invites_allow(
+    ctx: Context<'_>,
+    #[description = "Partner server ID or an invite to it"] server: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let partner = match server.trim().parse::<u64>() {
+        Ok(id) => Some(id).filter(|id| *id != 0),
+        Err(_) => spam_filter::resolve_invite(ctx.serenity_context(), &server).await,
+    };
+
+    let partner = match partner {
+        Some(id) => id,
+        None => {
+            ctx.say(":x: Invalid server ID or invite").await?;
+            return Ok(());
+        }
+    };
+
+    ctx.data().db.add_invite_allow(guild_id.get(), partner).await?;
+
+    ctx.say(format!(":white_check_mark: Invites to server {} are now allowed", partner))
+        .await?;
+
+    Ok(())
+}
+
+/// Stop allowing invites to a partner server
+#[poise::command(slash_command, prefix_command, rename = "remove")]
+pub async fn invites_remove(
+    ctx: Context<'_>,
+    #[description = "Partner server ID"] server_id: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+    let partner: u64 = server_id.trim().parse().map_err(|_| "Invalid server ID")?;
+
+    if ctx.data().db.remove_invite_allow(guild_id.get(), partner).await? {
+        ctx.say(format!(":white_check_mark: Invites to server {} are no longer allowed", partner))
+            .await?;
+    } else {
+        ctx.say(format!(":x: Server {} isn't a partner", partner)).await?;
+    }
+
+    Ok(())
+}
+
+/// List partner servers whose invites are allowed
+#[poise::command(slash_command, prefix_command, rename = "list")]
+pub async fn invites_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let partners = ctx.data().db.get_invite_allowlist(guild_id.get()).await?;
+
+    if partners.is_empty() {
+        ctx.say("No partner servers configured. Only invites to this server are allowed.")
+            .await?;
+    } else {
+        let mut message = String::from("**Partner servers:**\n");
+        for partner in partners {
+            let name = ctx
+                .cache()
+                .guild(serenity::GuildId::new(partner))
+                .map(|g| g.name.clone())
+                .unwrap_or_else(|| "Unknown server".to_string());
+            message.push_str(&format!("• {} ({})\n", name, partner));
+        }
+        ctx.say(message).await?;
+    }
+
+    Ok(())
+}
+
+/// Manage roles and channels that skip spam filter rules
+#[poise::command(
+    slash_command,
+    prefix_command,
+    rename = "exempt",
+    subcommands("exempt_add", "exempt_remove", "exempt_list")
+)]
+pub async fn spam_filter_exempt(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Exempt a role or channel from a rule (or every rule)
+#[poise::command(slash_command, prefix_command, rename = "add")]
+pub async fn exempt_add(
+    ctx: Context<'_>,
+    #[description = "Role to exempt"] role: Option<serenity::Role>,
+    #[description = "Channel to exempt"] channel: Option<serenity::GuildChannel>,
+    #[description = "Rule to skip (all rules if omitted)"] rule: Option<SpamRule>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let Some((target_id, is_channel, target)) = exemption_target(role, channel) else {
+        ctx.say(":x: Specify either a role or a channel").await?;
+        return Ok(());
+    };
+    let rule_name = rule.map(|r| r.name()).unwrap_or(spam_filter::ALL_RULES);
+
+    ctx.data()
+        .db
+        .add_spam_exemption(&SpamExemption {
+            guild_id: guild_id.get(),
+            target_id,
+            is_channel,
+            rule: rule_name.to_string(),
+        })
+        .await?;
+    spam_filter::invalidate_config(guild_id.get()).await;
+
+    ctx.say(format!(
+        ":white_check_mark: {} is now exempt from {}",
+        target,
+        describe_exempt_rule(rule_name)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a role or channel exemption
+#[poise::command(slash_command, prefix_command, rename = "remove")]
+pub async fn exempt_remove(
+    ctx: Context<'_>,
+    #[description = "Exempted role"] role: Option<serenity::Role>,
+    #[description = "Exempted channel"] channel: Option<serenity::GuildChannel>,
+    #[description = "Exempted rule (all rules if omitted)"] rule: Option<SpamRule>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let Some((target_id, _, target)) = exemption_target(role, channel) else {
+        ctx.say(":x: Specify either a role or a channel").await?;
+        return Ok(());
+    };
+    let rule_name = rule.map(|r| r.name()).unwrap_or(spam_filter::ALL_RULES);
+
+    if ctx
+        .data()
+        .db
+        .remove_spam_exemption(guild_id.get(), target_id, rule_name)
+        .await?
+    {
+        spam_filter::invalidate_config(guild_id.get()).await;
+        ctx.say(format!(
+            ":white_check_mark: {} is no longer exempt from {}",
+            target,
+            describe_exempt_rule(rule_name)
+        ))
+        .await?;
+    } else {
+        ctx.say(format!(
+            ":x: {} isn't exempt from {}",
+            target,
+            describe_exempt_rule(rule_name)
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// List spam filter exemptions
+#[poise::command(slash_command, prefix_command, rename = "list")]
+pub async fn exempt_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
+
+    let exemptions = ctx.data().db.get_spam_exemptions(guild_id.get()).await?;
+
+    if exemptions.is_empty() {
+        ctx.say("No spam filter exemptions configured.").await?;
+    } else {
+        let mut message = String::from("**Spam filter exemptions:**\n");
+        for exemption in exemptions {
+            let target = if exemption.is_channel {
+                format!("<#{}>", exemption.target_id)
+            } else {
+                format!("<@&{}>", exemption.target_id)
+            };
+            message.push_str(&format!("• {} - {}\n", target, describe_exempt_rule(&exemption.rule)));
+        }
+        ctx.send(
+            poise::CreateReply::default()
+                .content(message)
+                .allowed_mentions(serenity::CreateAllowedMentions::new()),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Pick the role or channel an exemption applies to, as (ID, is channel, mention)
+fn exemption_target(
+    role: Option<serenity::Role>,
+    channel: Option<serenity::GuildChannel>,
+) -> Option<(u64, bool, String)> {
+    match (role, channel) {
+        (Some(role), None) => Some((role.id.get(), false, format!("**@{}**", role.name))),
+        (None, Some(channel)) => Some((channel.id.get(), true, format!("<#{}>", channel.id))),
+        _ => None,
+    }
+}
+
+fn describe_exempt_rule(rule: &str) -> String {
+    if rule == spam_filter::ALL_RULES {
+        "all rules".to_string()
+    } else {
+        format!("the `{}` rule", rule)
+    }
+}
+
+fn describe_limits(settings: &SpamFilterSettings) -> String {
+    format!(
+        "**Flood:** {} messages in {}s\n**Duplicates:** {} messages in {}s at {}% similarity\n\
+        **Mentions:** {}\n**Caps:** {}% of at least {} letters\n**Zalgo:** {}% combining characters\n\
+        **Emoji:** more than {}\n**Lines:** more than {}",
+        settings.flood_messages,
+        settings.flood_seconds,
+        settings.duplicate_count,
+        settings.duplicate_seconds,
+        settings.duplicate_similarity,
+        settings.mention_limit,
+        settings.caps_percent,
+        settings.caps_min_length,
+        settings.zalgo_percent,
+        settings.emoji_limit,
+        settings.newline_limit
+    )
+}
+
+fn describe_settings(settings: &spam_filter::RuleSettings) -> String {
+    if !settings.enabled {
+        return "disabled".to_string();
+    }
+
+    match settings.action {
+        SpamAction::Delete => "delete".to_string(),
+        SpamAction::Warn => "delete and warn".to_string(),
+        SpamAction::Timeout => format!(
+            "timeout for {} after {} warning(s)",
+            format_duration(settings.timeout_minutes * 60),
+            settings.threshold
+        ),
+        action => format!("{} after {} warning(s)", action.name(), settings.threshold),
+    }
+}
@@ -17,6 +17,7 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use crate::utils::{DurationParseError, HumanDuration};
 use crate::{Context, Error};
 use poise::serenity_prelude as serenity;
 
@@ -120,6 +121,18 @@ pub async fn delay(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Parse an auto-clean interval, where a bare number is hours like it always was
+fn parse_clean_interval(input: &str) -> Result<HumanDuration, DurationParseError> {
+    match input.trim().parse::<i64>() {
+        Ok(hours) if hours < 0 => Err(DurationParseError::Invalid(input.to_string())),
+        Ok(hours) => hours
+            .checked_mul(3600)
+            .map(HumanDuration::from_secs)
+            .ok_or_else(|| DurationParseError::Overflow(input.to_string())),
+        Err(_) => input.parse(),
+    }
+}
+
 /// Manage auto-clean settings for channels
 #[poise::command(
     slash_command,
@@ -137,18 +150,29 @@ pub async fn auto_clean(_ctx: Context<'_>) -> Result<(), Error> {
 pub async fn auto_clean_add(
     ctx: Context<'_>,
     #[description = "Channel to add auto-clean to"] channel: serenity::GuildChannel,
-    #[description = "Hours between each clean"] hours: i64,
-    #[description = "Minutes before clean to show warning"] warning_minutes: i64,
+    #[description = "Time between each clean in whole hours, e.g. 6 (hours), 6h or 1d"] interval: String,
+    #[description = "How long before a clean to show a warning, e.g. 10m"]
+    #[rest]
+    warning: HumanDuration,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be used in a guild")?;
 
-    if hours < 1 {
-        ctx.say(":x: Hours must be at least 1").await?;
+    let interval = match parse_clean_interval(&interval) {
+        Ok(interval) => interval,
+        Err(e) => {
+            ctx.say(format!(":x: Invalid interval: {}", e)).await?;
+            return Ok(());
+        }
+    };
+    if interval.as_secs() < 3600 || interval.as_secs() % 3600 != 0 {
+        ctx.say(":x: Interval must be a whole number of hours, at least 1h").await?;
         return Ok(());
     }
+    let hours = interval.as_secs() / 3600;
 
-    if warning_minutes < 1 || warning_minutes >= hours * 60 {
-        ctx.say(":x: Warning minutes must be at least 1 and less than the total clean time")
+    let warning_minutes = warning.as_minutes();
+    if warning.as_secs() % 60 != 0 || warning_minutes < 1 || warning_minutes >= hours * 60 {
+        ctx.say(":x: Warning must be a whole number of minutes, at least 1m and shorter than the interval")
             .await?;
         return Ok(());
     }
@@ -165,8 +189,8 @@ pub async fn auto_clean_add(
         .await?;
 
     ctx.say(format!(
-        ":white_check_mark: Auto-clean added for {}. Will clean every {} hour(s) with a warning {} minute(s) before.",
-        channel.name, hours, warning_minutes
+        ":white_check_mark: Auto-clean added for {}. Will clean every {} with a warning {} before.",
+        channel.name, interval, warning
     ))
     .await?;
 
@@ -260,3 +284,21 @@ pub async fn source(ctx: Context<'_>) -> Result<(), Error> {
     .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_interval_bare_numbers_are_hours() {
+        assert_eq!(parse_clean_interval("6").unwrap().as_secs(), 6 * 3600);
+        assert_eq!(parse_clean_interval(" 24 ").unwrap().as_secs(), 24 * 3600);
+        assert_eq!(parse_clean_interval("90m").unwrap().as_secs(), 90 * 60);
+        assert_eq!(parse_clean_interval("1d").unwrap().as_secs(), 86400);
+        assert!(matches!(parse_clean_interval("-3"), Err(DurationParseError::Invalid(_))));
+        assert!(matches!(
+            parse_clean_interval(&i64::MAX.to_string()),
+            Err(DurationParseError::Overflow(_))
+        ));
+    }
+}
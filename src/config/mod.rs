@@ -39,6 +39,12 @@ pub struct BotConfig {
     #[serde(default = "default_spam_max_warnings")]
     pub spam_max_warnings: u32,
 
+    #[serde(default = "default_spam_warning_decay_hours")]
+    pub spam_warning_decay_hours: u64,
+
+    #[serde(default = "default_phishing_domains_path")]
+    pub phishing_domains_path: String,
+
     #[serde(default)]
     pub ban_default_image: Option<String>,
 
@@ -61,6 +67,14 @@ fn default_spam_max_warnings() -> u32 {
     3
 }
 
+fn default_spam_warning_decay_hours() -> u64 {
+    24
+}
+
+fn default_phishing_domains_path() -> String {
+    "phishing_domains.txt".to_string()
+}
+
 impl Default for BotConfig {
     fn default() -> Self {
         Self {
@@ -69,6 +83,8 @@ impl Default for BotConfig {
             database_path: default_database_path(),
             master_users: Vec::new(),
             spam_max_warnings: default_spam_max_warnings(),
+            spam_warning_decay_hours: default_spam_warning_decay_hours(),
+            phishing_domains_path: default_phishing_domains_path(),
             ban_default_image: None,
             dm_message: None,
             insufficient_permissions_message: None,
@@ -35,6 +35,7 @@ pub struct PendingXp {
     pub guild_id: u64,
     pub channel_id: u64,
     pub xp_amount: i64,
+    #[allow(dead_code)]
     pub added_at: i64,
 }
 
@@ -77,6 +78,188 @@ pub struct BotBan {
     pub timestamp: i64,
 }
 
+#[derive(Debug, Clone)]
+pub struct Warning {
+    pub id: i64,
+    pub guild_id: u64,
+    pub user_id: u64,
+    pub moderator_id: u64,
+    pub reason: Option<String>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModAction {
+    pub id: i64,
+    pub guild_id: u64,
+    pub case_number: i64,
+    pub moderator_id: u64,
+    pub target_id: u64,
+    pub action: String,
+    pub reason: Option<String>,
+    // Length of the punishment in seconds, for timeouts and temporary bans
+    pub duration: Option<i64>,
+    pub timestamp: i64,
+    // Audit log entry this action was seen in, used to deduplicate imports
+    pub audit_entry_id: Option<u64>,
+}
+
+impl ModAction {
+    pub fn new(guild_id: u64, moderator_id: u64, target_id: u64, action: &str, reason: Option<&str>) -> Self {
+        Self {
+            id: 0,
+            guild_id,
+            case_number: 0,
+            moderator_id,
+            target_id,
+            action: action.to_string(),
+            reason: reason.map(str::to_string),
+            duration: None,
+            timestamp: chrono::Utc::now().timestamp(),
+            audit_entry_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModActionEdit {
+    pub editor_id: u64,
+    pub old_reason: Option<String>,
+    pub new_reason: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TempBan {
+    pub guild_id: u64,
+    pub user_id: u64,
+    pub moderator_id: u64,
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct EscalationStep {
+    pub guild_id: u64,
+    pub warn_count: i64,
+    pub action: String,
+    pub duration_minutes: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BanAppeal {
+    pub id: i64,
+    pub guild_id: u64,
+    pub user_id: u64,
+    // Ban case the appeal is against, if the ban was recorded
+    pub case_number: Option<i64>,
+    pub content: String,
+    // pending, accepted, denied or undelivered
+    pub status: String,
+    pub reviewer_id: Option<u64>,
+    pub created_at: i64,
+    pub reviewed_at: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserNote {
+    pub id: i64,
+    pub guild_id: u64,
+    pub user_id: u64,
+    pub author_id: u64,
+    pub content: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpamRuleConfig {
+    pub guild_id: u64,
+    pub rule: String,
+    pub enabled: bool,
+    pub action: String,
+    pub threshold: i64,
+    pub timeout_minutes: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkDomain {
+    pub guild_id: u64,
+    pub domain: String,
+    pub blocked: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpamFilterSettings {
+    pub guild_id: u64,
+    pub flood_messages: i64,
+    pub flood_seconds: i64,
+    pub duplicate_count: i64,
+    pub duplicate_seconds: i64,
+    pub duplicate_similarity: i64,
+    pub mention_limit: i64,
+    pub caps_percent: i64,
+    pub caps_min_length: i64,
+    pub zalgo_percent: i64,
+    pub emoji_limit: i64,
+    pub newline_limit: i64,
+    pub observe_mode: bool,
+    pub report_channel_id: Option<u64>,
+}
+
+impl SpamFilterSettings {
+    pub fn default_for(guild_id: u64) -> Self {
+        Self {
+            guild_id,
+            flood_messages: 8,
+            flood_seconds: 5,
+            duplicate_count: 3,
+            duplicate_seconds: 60,
+            duplicate_similarity: 90,
+            mention_limit: 8,
+            caps_percent: 70,
+            caps_min_length: 10,
+            zalgo_percent: 30,
+            emoji_limit: 15,
+            newline_limit: 15,
+            observe_mode: false,
+            report_channel_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpamExemption {
+    pub guild_id: u64,
+    pub target_id: u64,
+    pub is_channel: bool,
+    // Rule name, or "all" for every rule
+    pub rule: String,
+}
+
+type ActivityLogRow = (i64, String, String, String, Option<String>, Option<String>, i64);
+type ModActionRow = (i64, i64, String, String, String, Option<String>, Option<i64>, i64, Option<String>);
+
+const MOD_ACTION_COLUMNS: &str =
+    "id, case_number, moderator_id, target_id, action, reason, duration, timestamp, audit_entry_id";
+
+fn mod_action_from_row(guild_id: u64, row: ModActionRow) -> ModAction {
+    let (id, case_number, moderator_id, target_id, action, reason, duration, timestamp, audit_entry_id) = row;
+    ModAction {
+        id,
+        guild_id,
+        case_number,
+        moderator_id: moderator_id.parse().unwrap_or(0),
+        target_id: target_id.parse().unwrap_or(0),
+        action,
+        reason,
+        duration,
+        timestamp,
+        audit_entry_id: audit_entry_id.and_then(|id| id.parse().ok()),
+    }
+}
+type BanAppealRow = (i64, String, String, Option<i64>, String, String, Option<String>, i64, Option<i64>);
+type DmInboxRow = (i64, String, Option<String>, Option<String>, i64, i32);
+type SpamFilterSettingsRow = (i64, i64, i64, i64, i64, i64, i64, i64, i64, i64, i64, i32, Option<String>);
+
 impl Clone for Database {
     fn clone(&self) -> Self {
         Self {
@@ -99,6 +282,22 @@ impl Database {
         })
     }
 
+    /// Add a column to an existing table if it's missing, returning whether it was added
+    async fn ensure_column(&self, table: &str, column: &str, definition: &str) -> Result<bool> {
+        let columns: Vec<(String,)> = sqlx::query_as(&format!("SELECT name FROM pragma_table_info('{}')", table))
+            .fetch_all(&self.pool)
+            .await?;
+
+        if columns.iter().any(|(name,)| name == column) {
+            return Ok(false);
+        }
+
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(&self.pool)
+            .await?;
+        Ok(true)
+    }
+
     pub async fn init(&self) -> Result<()> {
         // Create guilds table
         sqlx::query(
@@ -113,6 +312,10 @@ impl Database {
         )
         .execute(&self.pool)
         .await?;
+        self.ensure_column("guilds", "mod_log_channel", "TEXT").await?;
+        self.ensure_column("guilds", "audit_import_cursor", "TEXT").await?;
+        self.ensure_column("guilds", "appeal_channel", "TEXT").await?;
+        self.ensure_column("guilds", "ban_dm_template", "TEXT").await?;
 
         // Create experiences table
         sqlx::query(
@@ -189,6 +392,47 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        // Per-guild case numbers, numbered in insertion order for existing rows
+        if self.ensure_column("mod_actions", "case_number", "INTEGER").await? {
+            sqlx::query(
+                r#"
+                UPDATE mod_actions SET case_number = (
+                    SELECT COUNT(*) FROM mod_actions AS earlier
+                    WHERE earlier.guild_id = mod_actions.guild_id AND earlier.id <= mod_actions.id
+                )
+                "#,
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+        self.ensure_column("mod_actions", "duration", "INTEGER").await?;
+        self.ensure_column("mod_actions", "audit_entry_id", "TEXT").await?;
+        sqlx::query(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_mod_actions_audit ON mod_actions(guild_id, audit_entry_id) WHERE audit_entry_id IS NOT NULL",
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query("CREATE UNIQUE INDEX IF NOT EXISTS idx_mod_actions_case ON mod_actions(guild_id, case_number)")
+            .execute(&self.pool)
+            .await?;
+
+        // Mod action reason edit history
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS mod_action_edits (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                case_number INTEGER NOT NULL,
+                editor_id TEXT NOT NULL,
+                old_reason TEXT,
+                new_reason TEXT NOT NULL,
+                timestamp INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create level role mappings table
         sqlx::query(
             r#"
@@ -286,134 +530,738 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
-        Ok(())
-    }
-
-    // Guild operations
-    pub async fn init_guild(&self, guild_id: u64) -> Result<()> {
-        sqlx::query("INSERT OR IGNORE INTO guilds(id) VALUES(?)")
-            .bind(guild_id.to_string())
+        // Warnings table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS warnings (
+                id INTEGER PRIMARY KEY,
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                moderator_id TEXT NOT NULL,
+                reason TEXT,
+                timestamp INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_warnings_guild_user ON warnings(guild_id, user_id)")
             .execute(&self.pool)
             .await?;
-        Ok(())
-    }
-
-    pub async fn get_prefix(&self, guild_id: u64) -> Result<Option<String>> {
-        let result: Option<(Option<String>,)> =
-            sqlx::query_as("SELECT prefix FROM guilds WHERE id = ?")
-                .bind(guild_id.to_string())
-                .fetch_optional(&self.pool)
-                .await?;
-
-        Ok(result.and_then(|(prefix,)| prefix))
-    }
 
-    pub async fn set_prefix(&self, guild_id: u64, prefix: &str) -> Result<()> {
-        self.init_guild(guild_id).await?;
-        sqlx::query("UPDATE guilds SET prefix = ? WHERE id = ?")
-            .bind(prefix)
-            .bind(guild_id.to_string())
+        // Ban appeals table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS ban_appeals (
+                id INTEGER PRIMARY KEY,
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                case_number INTEGER,
+                content TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                reviewer_id TEXT,
+                created_at INTEGER NOT NULL,
+                reviewed_at INTEGER
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_ban_appeals_guild_user ON ban_appeals(guild_id, user_id)")
             .execute(&self.pool)
             .await?;
-        Ok(())
-    }
 
-    // Mod action operations
-    pub async fn add_mod_action(
-        &self,
-        guild_id: u64,
-        moderator_id: u64,
-        target_id: u64,
-        action: &str,
-        reason: Option<&str>,
-        timestamp: i64,
-    ) -> Result<()> {
-        self.init_guild(guild_id).await?;
+        // Temporary bans table
         sqlx::query(
-            "INSERT INTO mod_actions(guild_id, moderator_id, target_id, action, reason, timestamp) VALUES(?, ?, ?, ?, ?, ?)",
+            r#"
+            CREATE TABLE IF NOT EXISTS temp_bans (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                moderator_id TEXT NOT NULL,
+                expires_at INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, user_id)
+            )
+            "#,
         )
-        .bind(guild_id.to_string())
-        .bind(moderator_id.to_string())
-        .bind(target_id.to_string())
-        .bind(action)
-        .bind(reason)
-        .bind(timestamp)
         .execute(&self.pool)
         .await?;
-        Ok(())
-    }
 
-    pub async fn get_mod_stats(&self, guild_id: u64) -> Result<ModStats> {
-        // Get action counts
-        let action_counts: Vec<(String, i64)> = sqlx::query_as(
-            "SELECT action, COUNT(*) as count FROM mod_actions WHERE guild_id = ? GROUP BY action",
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_temp_bans_expires ON temp_bans(expires_at)")
+            .execute(&self.pool)
+            .await?;
+
+        // Warning escalation ladder table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS escalation_steps (
+                guild_id TEXT NOT NULL,
+                warn_count INTEGER NOT NULL,
+                action TEXT NOT NULL,
+                duration_minutes INTEGER,
+                PRIMARY KEY (guild_id, warn_count)
+            )
+            "#,
         )
-        .bind(guild_id.to_string())
-        .fetch_all(&self.pool)
+        .execute(&self.pool)
         .await?;
 
-        // Get top moderators
-        let top_mods: Vec<(String, i64)> = sqlx::query_as(
-            "SELECT moderator_id, COUNT(*) as count FROM mod_actions WHERE guild_id = ? GROUP BY moderator_id ORDER BY count DESC LIMIT 10",
+        // Highest escalation step applied to each user
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS escalation_applied (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                warn_count INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, user_id)
+            )
+            "#,
         )
-        .bind(guild_id.to_string())
-        .fetch_all(&self.pool)
+        .execute(&self.pool)
         .await?;
 
-        // Get mod counts by action
-        let mod_counts: Vec<(String, String, i64)> = sqlx::query_as(
-            "SELECT moderator_id, action, COUNT(*) as count FROM mod_actions WHERE guild_id = ? GROUP BY moderator_id, action",
+        // Moderator notes table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS user_notes (
+                id INTEGER PRIMARY KEY,
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                author_id TEXT NOT NULL,
+                content TEXT NOT NULL,
+                timestamp INTEGER NOT NULL
+            )
+            "#,
         )
-        .bind(guild_id.to_string())
-        .fetch_all(&self.pool)
+        .execute(&self.pool)
         .await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_user_notes_guild_user ON user_notes(guild_id, user_id)")
+            .execute(&self.pool)
+            .await?;
 
-        // Get total count
-        let total: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM mod_actions WHERE guild_id = ?",
+        // Spam filter warnings table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS spam_warnings (
+                id INTEGER PRIMARY KEY,
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                reason TEXT,
+                timestamp INTEGER NOT NULL
+            )
+            "#,
         )
-        .bind(guild_id.to_string())
-        .fetch_one(&self.pool)
+        .execute(&self.pool)
         .await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_spam_warnings_guild_user ON spam_warnings(guild_id, user_id)")
+            .execute(&self.pool)
+            .await?;
 
-        Ok(ModStats {
-            action_counts,
-            top_mods,
-            mod_counts,
-            total: total.0,
-        })
-    }
-
-    // XP operations
-    pub async fn get_xp(&self, guild_id: u64, user_id: u64) -> Result<(i64, i64)> {
-        let result: Option<(i64, i64)> = sqlx::query_as(
-            "SELECT xp, level FROM experiences WHERE guild_id = ? AND user_id = ?",
+        // Spam filter rule overrides table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS spam_filter_rules (
+                guild_id TEXT NOT NULL,
+                rule TEXT NOT NULL,
+                enabled INTEGER DEFAULT 1,
+                action TEXT NOT NULL,
+                threshold INTEGER NOT NULL,
+                timeout_minutes INTEGER DEFAULT 10,
+                PRIMARY KEY (guild_id, rule)
+            )
+            "#,
         )
-        .bind(guild_id.to_string())
-        .bind(user_id.to_string())
-        .fetch_optional(&self.pool)
+        .execute(&self.pool)
         .await?;
 
-        Ok(result.unwrap_or((0, 0)))
-    }
-
-    pub async fn set_xp(&self, guild_id: u64, user_id: u64, xp: i64, level: i64) -> Result<()> {
+        // Spam filter link allow/block list table
         sqlx::query(
             r#"
-            INSERT INTO experiences(user_id, guild_id, xp, level) VALUES(?, ?, ?, ?)
-            ON CONFLICT(user_id, guild_id) DO UPDATE SET xp = ?, level = ?
+            CREATE TABLE IF NOT EXISTS spam_link_domains (
+                guild_id TEXT NOT NULL,
+                domain TEXT NOT NULL,
+                blocked INTEGER DEFAULT 0,
+                PRIMARY KEY (guild_id, domain)
+            )
             "#,
         )
-        .bind(user_id.to_string())
-        .bind(guild_id.to_string())
-        .bind(xp)
-        .bind(level)
-        .bind(xp)
-        .bind(level)
         .execute(&self.pool)
         .await?;
-        Ok(())
-    }
+
+        // Spam filter thresholds table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS spam_filter_settings (
+                guild_id TEXT PRIMARY KEY,
+                flood_messages INTEGER DEFAULT 8,
+                flood_seconds INTEGER DEFAULT 5,
+                duplicate_count INTEGER DEFAULT 3,
+                duplicate_seconds INTEGER DEFAULT 60,
+                duplicate_similarity INTEGER DEFAULT 90,
+                mention_limit INTEGER DEFAULT 8,
+                caps_percent INTEGER DEFAULT 70,
+                caps_min_length INTEGER DEFAULT 10,
+                zalgo_percent INTEGER DEFAULT 30,
+                emoji_limit INTEGER DEFAULT 15,
+                newline_limit INTEGER DEFAULT 15,
+                observe_mode INTEGER DEFAULT 0,
+                report_channel_id TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Spam filter exemptions table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS spam_filter_exemptions (
+                guild_id TEXT NOT NULL,
+                target_id TEXT NOT NULL,
+                is_channel INTEGER DEFAULT 0,
+                rule TEXT NOT NULL,
+                PRIMARY KEY (guild_id, target_id, rule)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Spam filter partner invite table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS spam_invite_allowlist (
+                guild_id TEXT NOT NULL,
+                partner_guild_id TEXT NOT NULL,
+                PRIMARY KEY (guild_id, partner_guild_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Guild operations
+    pub async fn init_guild(&self, guild_id: u64) -> Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO guilds(id) VALUES(?)")
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_prefix(&self, guild_id: u64) -> Result<Option<String>> {
+        let result: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT prefix FROM guilds WHERE id = ?")
+                .bind(guild_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.and_then(|(prefix,)| prefix))
+    }
+
+    pub async fn set_prefix(&self, guild_id: u64, prefix: &str) -> Result<()> {
+        self.init_guild(guild_id).await?;
+        sqlx::query("UPDATE guilds SET prefix = ? WHERE id = ?")
+            .bind(prefix)
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn is_spam_filter_enabled(&self, guild_id: u64) -> Result<bool> {
+        let result: Option<(Option<i64>,)> =
+            sqlx::query_as("SELECT spam_filter FROM guilds WHERE id = ?")
+                .bind(guild_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.and_then(|(enabled,)| enabled).unwrap_or(1) != 0)
+    }
+
+    pub async fn set_spam_filter_enabled(&self, guild_id: u64, enabled: bool) -> Result<()> {
+        self.init_guild(guild_id).await?;
+        sqlx::query("UPDATE guilds SET spam_filter = ? WHERE id = ?")
+            .bind(enabled as i32)
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Mod action operations
+    pub async fn get_mod_log_channel(&self, guild_id: u64) -> Result<Option<u64>> {
+        let result: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT mod_log_channel FROM guilds WHERE id = ?")
+                .bind(guild_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.and_then(|(c,)| c).and_then(|c| c.parse().ok()))
+    }
+
+    pub async fn set_mod_log_channel(&self, guild_id: u64, channel_id: Option<u64>) -> Result<()> {
+        self.init_guild(guild_id).await?;
+        sqlx::query("UPDATE guilds SET mod_log_channel = ? WHERE id = ?")
+            .bind(channel_id.map(|c| c.to_string()))
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_appeal_channel(&self, guild_id: u64) -> Result<Option<u64>> {
+        let result: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT appeal_channel FROM guilds WHERE id = ?")
+                .bind(guild_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.and_then(|(c,)| c).and_then(|c| c.parse().ok()))
+    }
+
+    pub async fn set_appeal_channel(&self, guild_id: u64, channel_id: Option<u64>) -> Result<()> {
+        self.init_guild(guild_id).await?;
+        sqlx::query("UPDATE guilds SET appeal_channel = ? WHERE id = ?")
+            .bind(channel_id.map(|c| c.to_string()))
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_ban_dm_template(&self, guild_id: u64) -> Result<Option<String>> {
+        let result: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT ban_dm_template FROM guilds WHERE id = ?")
+                .bind(guild_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.and_then(|(t,)| t))
+    }
+
+    pub async fn set_ban_dm_template(&self, guild_id: u64, template: Option<&str>) -> Result<()> {
+        self.init_guild(guild_id).await?;
+        sqlx::query("UPDATE guilds SET ban_dm_template = ? WHERE id = ?")
+            .bind(template)
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Ban image operations. A banner of None is the guild's default image.
+    pub async fn get_ban_image(&self, guild_id: u64, banner: Option<u64>) -> Result<Option<String>> {
+        let result: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT image FROM ban_images WHERE guild_id = ? AND banner IS ? LIMIT 1")
+                .bind(guild_id.to_string())
+                .bind(banner.map(|b| b.to_string()))
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.and_then(|(image,)| image))
+    }
+
+    pub async fn set_ban_image(&self, guild_id: u64, banner: Option<u64>, image: &str) -> Result<()> {
+        // The table has no unique key, so replace by hand
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM ban_images WHERE guild_id = ? AND banner IS ?")
+            .bind(guild_id.to_string())
+            .bind(banner.map(|b| b.to_string()))
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("INSERT INTO ban_images (guild_id, banner, image) VALUES (?, ?, ?)")
+            .bind(guild_id.to_string())
+            .bind(banner.map(|b| b.to_string()))
+            .bind(image)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Remove a ban image, returning whether there was one
+    pub async fn remove_ban_image(&self, guild_id: u64, banner: Option<u64>) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM ban_images WHERE guild_id = ? AND banner IS ?")
+            .bind(guild_id.to_string())
+            .bind(banner.map(|b| b.to_string()))
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a moderation action, returning its case number
+    pub async fn add_mod_action(&self, action: &ModAction) -> Result<i64> {
+        self.init_guild(action.guild_id).await?;
+        let (case_number,): (i64,) = sqlx::query_as(
+            r#"
+            INSERT INTO mod_actions(guild_id, moderator_id, target_id, action, reason, duration, timestamp, audit_entry_id, case_number)
+            SELECT ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(MAX(case_number), 0) + 1 FROM mod_actions WHERE guild_id = ?
+            RETURNING case_number
+            "#,
+        )
+        .bind(action.guild_id.to_string())
+        .bind(action.moderator_id.to_string())
+        .bind(action.target_id.to_string())
+        .bind(&action.action)
+        .bind(&action.reason)
+        .bind(action.duration)
+        .bind(action.timestamp)
+        .bind(action.audit_entry_id.map(|id| id.to_string()))
+        .bind(action.guild_id.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(case_number)
+    }
+
+    pub async fn has_recent_mod_action(&self, guild_id: u64, target_id: u64, action: &str, since: i64) -> Result<bool> {
+        let result: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM mod_actions WHERE guild_id = ? AND target_id = ? AND action = ? AND timestamp >= ?",
+        )
+        .bind(guild_id.to_string())
+        .bind(target_id.to_string())
+        .bind(action)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.0 > 0)
+    }
+
+    /// Whether the user's most recent ban or unban on record left them banned
+    pub async fn is_ban_on_record(&self, guild_id: u64, target_id: u64) -> Result<bool> {
+        let result: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT action FROM mod_actions
+            WHERE guild_id = ? AND target_id = ? AND action IN ('ban', 'tempban', 'softban', 'unban')
+            ORDER BY timestamp DESC, case_number DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(guild_id.to_string())
+        .bind(target_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.is_some_and(|(action,)| matches!(action.as_str(), "ban" | "tempban")))
+    }
+
+    /// Record an action seen in the audit log unless it's already known, returning whether it was new.
+    /// A ban list import placeholder (unknown moderator) for the same user gets the real details instead.
+    pub async fn import_mod_action(&self, action: &ModAction) -> Result<bool> {
+        let audit_entry_id = action.audit_entry_id.map(|id| id.to_string());
+
+        // Same entry, or the same action recorded live around the same time
+        let existing: Option<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT id FROM mod_actions
+            WHERE guild_id = ? AND target_id = ? AND action = ?
+                AND (audit_entry_id = ? OR (audit_entry_id IS NULL AND ABS(timestamp - ?) <= 60))
+            LIMIT 1
+            "#,
+        )
+        .bind(action.guild_id.to_string())
+        .bind(action.target_id.to_string())
+        .bind(&action.action)
+        .bind(&audit_entry_id)
+        .bind(action.timestamp)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some((id,)) = existing {
+            sqlx::query("UPDATE mod_actions SET audit_entry_id = COALESCE(audit_entry_id, ?) WHERE id = ?")
+                .bind(&audit_entry_id)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            return Ok(false);
+        }
+
+        if action.action == "ban" {
+            let placeholder = sqlx::query(
+                r#"
+                UPDATE mod_actions SET moderator_id = ?, reason = COALESCE(?, reason), timestamp = ?, audit_entry_id = ?
+                WHERE id = (
+                    SELECT id FROM mod_actions
+                    WHERE guild_id = ? AND target_id = ? AND action = 'ban' AND moderator_id = '0' AND audit_entry_id IS NULL
+                    LIMIT 1
+                )
+                "#,
+            )
+            .bind(action.moderator_id.to_string())
+            .bind(&action.reason)
+            .bind(action.timestamp)
+            .bind(&audit_entry_id)
+            .bind(action.guild_id.to_string())
+            .bind(action.target_id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+            if placeholder.rows_affected() > 0 {
+                return Ok(true);
+            }
+        }
+
+        self.add_mod_action(action).await?;
+        Ok(true)
+    }
+
+    pub async fn get_audit_import_cursor(&self, guild_id: u64) -> Result<Option<u64>> {
+        let result: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT audit_import_cursor FROM guilds WHERE id = ?")
+                .bind(guild_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.and_then(|(c,)| c).and_then(|c| c.parse().ok()))
+    }
+
+    pub async fn set_audit_import_cursor(&self, guild_id: u64, entry_id: u64) -> Result<()> {
+        self.init_guild(guild_id).await?;
+        sqlx::query("UPDATE guilds SET audit_import_cursor = ? WHERE id = ?")
+            .bind(entry_id.to_string())
+            .bind(guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_mod_case(&self, guild_id: u64, case_number: i64) -> Result<Option<ModAction>> {
+        let result: Option<ModActionRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM mod_actions WHERE guild_id = ? AND case_number = ?",
+            MOD_ACTION_COLUMNS
+        ))
+        .bind(guild_id.to_string())
+        .bind(case_number)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.map(|row| mod_action_from_row(guild_id, row)))
+    }
+
+    /// All of a guild's actions from `since` onwards, oldest case first
+    pub async fn get_mod_actions(&self, guild_id: u64, since: Option<i64>) -> Result<Vec<ModAction>> {
+        let results: Vec<ModActionRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM mod_actions WHERE guild_id = ? AND timestamp >= ? ORDER BY case_number",
+            MOD_ACTION_COLUMNS
+        ))
+        .bind(guild_id.to_string())
+        .bind(since.unwrap_or(0))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|row| mod_action_from_row(guild_id, row))
+            .collect())
+    }
+
+    /// Every action taken against a user, newest case first
+    pub async fn get_user_mod_actions(&self, guild_id: u64, target_id: u64) -> Result<Vec<ModAction>> {
+        let results: Vec<ModActionRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM mod_actions WHERE guild_id = ? AND target_id = ? ORDER BY case_number DESC",
+            MOD_ACTION_COLUMNS
+        ))
+        .bind(guild_id.to_string())
+        .bind(target_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|row| mod_action_from_row(guild_id, row))
+            .collect())
+    }
+
+    /// How many times each action was taken against a user, most common first
+    pub async fn count_user_mod_actions(&self, guild_id: u64, target_id: u64) -> Result<Vec<(String, i64)>> {
+        let results: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT action, COUNT(*) as count FROM mod_actions WHERE guild_id = ? AND target_id = ? GROUP BY action ORDER BY count DESC",
+        )
+        .bind(guild_id.to_string())
+        .bind(target_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results)
+    }
+
+    /// Change a case's reason, keeping the previous one in the edit history
+    pub async fn update_mod_case_reason(
+        &self,
+        guild_id: u64,
+        case_number: i64,
+        editor_id: u64,
+        new_reason: &str,
+    ) -> Result<bool> {
+        let Some(case) = self.get_mod_case(guild_id, case_number).await? else {
+            return Ok(false);
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO mod_action_edits(guild_id, case_number, editor_id, old_reason, new_reason, timestamp) VALUES(?, ?, ?, ?, ?, ?)",
+        )
+        .bind(guild_id.to_string())
+        .bind(case_number)
+        .bind(editor_id.to_string())
+        .bind(&case.reason)
+        .bind(new_reason)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE mod_actions SET reason = ? WHERE id = ?")
+            .bind(new_reason)
+            .bind(case.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn get_mod_case_edits(&self, guild_id: u64, case_number: i64) -> Result<Vec<ModActionEdit>> {
+        let results: Vec<(String, Option<String>, String, i64)> = sqlx::query_as(
+            "SELECT editor_id, old_reason, new_reason, timestamp FROM mod_action_edits WHERE guild_id = ? AND case_number = ? ORDER BY id",
+        )
+        .bind(guild_id.to_string())
+        .bind(case_number)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(editor_id, old_reason, new_reason, timestamp)| ModActionEdit {
+                editor_id: editor_id.parse().unwrap_or(0),
+                old_reason,
+                new_reason,
+                timestamp,
+            })
+            .collect())
+    }
+
+    pub async fn get_mod_stats(&self, guild_id: u64, filter: &ModStatsFilter) -> Result<ModStats> {
+        // Get action counts
+        let action_counts: Vec<(String, i64)> = bind_stats_filter(
+            sqlx::query_as(&format!(
+                "SELECT action, COUNT(*) as count FROM mod_actions WHERE {} GROUP BY action",
+                MOD_STATS_FILTER
+            )),
+            guild_id,
+            filter,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Get moderators, most active first
+        let top_mods: Vec<(String, i64)> = bind_stats_filter(
+            sqlx::query_as(&format!(
+                "SELECT moderator_id, COUNT(*) as count FROM mod_actions WHERE {} GROUP BY moderator_id ORDER BY count DESC",
+                MOD_STATS_FILTER
+            )),
+            guild_id,
+            filter,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Get mod counts by action
+        let mod_counts: Vec<(String, String, i64)> = bind_stats_filter(
+            sqlx::query_as(&format!(
+                "SELECT moderator_id, action, COUNT(*) as count FROM mod_actions WHERE {} GROUP BY moderator_id, action ORDER BY count DESC",
+                MOD_STATS_FILTER
+            )),
+            guild_id,
+            filter,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Get counts per week (labelled by its Monday) or month. %W would split the days
+        // before the first Monday of a year into a partial week 00.
+        let bucket = if filter.monthly {
+            "strftime('%Y-%m', timestamp, 'unixepoch')"
+        } else {
+            "date(timestamp, 'unixepoch', 'weekday 0', '-6 days')"
+        };
+        let trend: Vec<(String, i64)> = bind_stats_filter(
+            sqlx::query_as(&format!(
+                "SELECT {} as bucket, COUNT(*) as count FROM mod_actions WHERE {} GROUP BY bucket ORDER BY bucket",
+                bucket, MOD_STATS_FILTER
+            )),
+            guild_id,
+            filter,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Get counts per day and action, for charts
+        let daily: Vec<(String, String, i64)> = bind_stats_filter(
+            sqlx::query_as(&format!(
+                "SELECT strftime('%Y-%m-%d', timestamp, 'unixepoch') as day, action, COUNT(*) as count FROM mod_actions WHERE {} GROUP BY day, action ORDER BY day",
+                MOD_STATS_FILTER
+            )),
+            guild_id,
+            filter,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Get total count
+        let total: (i64,) = bind_stats_filter(
+            sqlx::query_as(&format!("SELECT COUNT(*) FROM mod_actions WHERE {}", MOD_STATS_FILTER)),
+            guild_id,
+            filter,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ModStats {
+            action_counts,
+            top_mods,
+            mod_counts,
+            trend,
+            daily,
+            total: total.0,
+        })
+    }
+
+    // XP operations
+    pub async fn get_xp(&self, guild_id: u64, user_id: u64) -> Result<(i64, i64)> {
+        let result: Option<(i64, i64)> = sqlx::query_as(
+            "SELECT xp, level FROM experiences WHERE guild_id = ? AND user_id = ?",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.unwrap_or((0, 0)))
+    }
+
+    pub async fn set_xp(&self, guild_id: u64, user_id: u64, xp: i64, level: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO experiences(user_id, guild_id, xp, level) VALUES(?, ?, ?, ?)
+            ON CONFLICT(user_id, guild_id) DO UPDATE SET xp = ?, level = ?
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(guild_id.to_string())
+        .bind(xp)
+        .bind(level)
+        .bind(xp)
+        .bind(level)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
 
     // Auto-clean operations
     pub async fn get_clean(&self, guild_id: u64, channel_name: &str) -> Result<Option<CleanConfig>> {
@@ -421,324 +1269,946 @@ impl Database {
             "SELECT time_between_cleans, warning_time, remaining_time FROM channel_cleans WHERE guild_id = ? AND channel_name = ?",
         )
         .bind(guild_id.to_string())
-        .bind(channel_name.to_lowercase())
+        .bind(channel_name.to_lowercase())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.map(|(time_between, warning, remaining)| CleanConfig {
+            guild_id,
+            channel_name: channel_name.to_string(),
+            time_between_cleans: time_between,
+            warning_time: warning,
+            remaining_time: remaining,
+        }))
+    }
+
+    pub async fn set_clean(
+        &self,
+        guild_id: u64,
+        channel_name: &str,
+        time_between: i64,
+        warning_time: i64,
+        remaining_time: Option<i64>,
+    ) -> Result<()> {
+        let remaining = remaining_time.unwrap_or(time_between * 60);
+        sqlx::query(
+            r#"
+            INSERT INTO channel_cleans(guild_id, channel_name, time_between_cleans, warning_time, remaining_time)
+            VALUES(?, ?, ?, ?, ?)
+            ON CONFLICT(guild_id, channel_name) DO UPDATE SET
+                time_between_cleans = ?, warning_time = ?, remaining_time = ?
+            "#,
+        )
+        .bind(guild_id.to_string())
+        .bind(channel_name.to_lowercase())
+        .bind(time_between)
+        .bind(warning_time)
+        .bind(remaining)
+        .bind(time_between)
+        .bind(warning_time)
+        .bind(remaining)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_clean(&self, guild_id: u64, channel_name: &str) -> Result<()> {
+        sqlx::query("DELETE FROM channel_cleans WHERE guild_id = ? AND channel_name = ?")
+            .bind(guild_id.to_string())
+            .bind(channel_name.to_lowercase())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_all_cleans(&self) -> Result<Vec<CleanConfig>> {
+        let results: Vec<(String, String, i64, i64, i64)> = sqlx::query_as(
+            "SELECT guild_id, channel_name, time_between_cleans, warning_time, remaining_time FROM channel_cleans",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(gid, cname, time_between, warning, remaining)| CleanConfig {
+                guild_id: gid.parse().unwrap_or(0),
+                channel_name: cname,
+                time_between_cleans: time_between,
+                warning_time: warning,
+                remaining_time: remaining,
+            })
+            .collect())
+    }
+
+    // XP Batching operations
+    pub async fn add_xp_to_batch(&self, user_id: u64, guild_id: u64, channel_id: u64, xp: i64) {
+        let key = format!("{}:{}", guild_id, user_id);
+        let mut pending = self.pending_xp.lock().await;
+
+        if let Some(entry) = pending.get_mut(&key) {
+            entry.xp_amount += xp;
+            entry.channel_id = channel_id;
+        } else {
+            pending.insert(
+                key,
+                PendingXp {
+                    user_id,
+                    guild_id,
+                    channel_id,
+                    xp_amount: xp,
+                    added_at: chrono::Utc::now().timestamp(),
+                },
+            );
+        }
+    }
+
+    pub async fn flush_xp_batch(&self) -> Result<Vec<(u64, u64, u64, i64, i64)>> {
+        let mut pending = self.pending_xp.lock().await;
+        if pending.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let to_process: HashMap<String, PendingXp> = std::mem::take(&mut *pending);
+        drop(pending);
+
+        let mut level_ups = Vec::new();
+
+        for (_, xp_entry) in to_process {
+            let (current_xp, current_level) = self.get_xp(xp_entry.guild_id, xp_entry.user_id).await?;
+            let new_xp = current_xp + xp_entry.xp_amount;
+            let new_level = ((new_xp as f64) / 100.0).sqrt() as i64;
+
+            self.set_xp(xp_entry.guild_id, xp_entry.user_id, new_xp, new_level).await?;
+
+            if new_level > current_level {
+                level_ups.push((xp_entry.user_id, xp_entry.guild_id, xp_entry.channel_id, new_level, new_xp));
+            }
+        }
+
+        Ok(level_ups)
+    }
+
+    // Voice XP config operations
+    pub async fn get_voice_xp_config(&self, guild_id: u64) -> Result<VoiceXpConfig> {
+        let result: Option<(i32, i32, i32, i32)> = sqlx::query_as(
+            "SELECT enabled, xp_per_minute, min_users, ignore_afk FROM voice_xp_config WHERE guild_id = ?",
+        )
+        .bind(guild_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result
+            .map(|(enabled, xp_per_min, min_users, ignore_afk)| VoiceXpConfig {
+                guild_id,
+                enabled: enabled != 0,
+                xp_per_minute: xp_per_min,
+                min_users,
+                ignore_afk: ignore_afk != 0,
+            })
+            .unwrap_or(VoiceXpConfig {
+                guild_id,
+                enabled: false,
+                xp_per_minute: 5,
+                min_users: 2,
+                ignore_afk: true,
+            }))
+    }
+
+    pub async fn set_voice_xp_config(&self, config: &VoiceXpConfig) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO voice_xp_config (guild_id, enabled, xp_per_minute, min_users, ignore_afk)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET
+                enabled = ?, xp_per_minute = ?, min_users = ?, ignore_afk = ?
+            "#,
+        )
+        .bind(config.guild_id.to_string())
+        .bind(config.enabled as i32)
+        .bind(config.xp_per_minute)
+        .bind(config.min_users)
+        .bind(config.ignore_afk as i32)
+        .bind(config.enabled as i32)
+        .bind(config.xp_per_minute)
+        .bind(config.min_users)
+        .bind(config.ignore_afk as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Activity log operations
+    pub async fn log_activity(&self, log: &ActivityLog) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO activity_log (guild_id, user_id, channel_id, event_type, old_content, new_content, timestamp)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(log.guild_id.to_string())
+        .bind(log.user_id.to_string())
+        .bind(log.channel_id.to_string())
+        .bind(&log.event_type)
+        .bind(&log.old_content)
+        .bind(&log.new_content)
+        .bind(log.timestamp)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_activity_logs(&self, guild_id: u64, limit: i64) -> Result<Vec<ActivityLog>> {
+        let results: Vec<ActivityLogRow> =
+            sqlx::query_as(
+                "SELECT id, user_id, channel_id, event_type, old_content, new_content, timestamp FROM activity_log WHERE guild_id = ? ORDER BY timestamp DESC LIMIT ?",
+            )
+            .bind(guild_id.to_string())
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(id, uid, cid, event_type, old_content, new_content, timestamp)| ActivityLog {
+                id,
+                guild_id,
+                user_id: uid.parse().unwrap_or(0),
+                channel_id: cid.parse().unwrap_or(0),
+                event_type,
+                old_content,
+                new_content,
+                timestamp,
+            })
+            .collect())
+    }
+
+    // DM inbox operations
+    pub async fn save_dm(&self, dm: &DmInbox) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO dm_inbox (user_id, username, content, timestamp, read_status)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(dm.user_id.to_string())
+        .bind(&dm.username)
+        .bind(&dm.content)
+        .bind(dm.timestamp)
+        .bind(dm.read_status as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_dms(&self, limit: i64) -> Result<Vec<DmInbox>> {
+        let results: Vec<DmInboxRow> = sqlx::query_as(
+            "SELECT id, user_id, username, content, timestamp, read_status FROM dm_inbox ORDER BY timestamp DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(id, uid, username, content, timestamp, read_status)| DmInbox {
+                id,
+                user_id: uid.parse().unwrap_or(0),
+                username: username.unwrap_or_default(),
+                content: content.unwrap_or_default(),
+                timestamp,
+                read_status: read_status != 0,
+            })
+            .collect())
+    }
+
+    pub async fn mark_dm_read(&self, dm_id: i64) -> Result<()> {
+        sqlx::query("UPDATE dm_inbox SET read_status = 1 WHERE id = ?")
+            .bind(dm_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_unread_dm_count(&self) -> Result<i64> {
+        let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dm_inbox WHERE read_status = 0")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(result.0)
+    }
+
+    // Bot ban operations
+    pub async fn add_bot_ban(&self, ban: &BotBan) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO bot_bans (user_id, banned_by, reason, timestamp)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(ban.user_id.to_string())
+        .bind(ban.banned_by.to_string())
+        .bind(&ban.reason)
+        .bind(ban.timestamp)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_bot_ban(&self, user_id: u64) -> Result<()> {
+        sqlx::query("DELETE FROM bot_bans WHERE user_id = ?")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn is_bot_banned(&self, user_id: u64) -> Result<bool> {
+        let result: Option<(i64,)> = sqlx::query_as("SELECT 1 FROM bot_bans WHERE user_id = ?")
+            .bind(user_id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(result.is_some())
+    }
+
+    pub async fn get_bot_bans(&self, limit: i64) -> Result<Vec<BotBan>> {
+        let results: Vec<(String, String, Option<String>, i64)> = sqlx::query_as(
+            "SELECT user_id, banned_by, reason, timestamp FROM bot_bans ORDER BY timestamp DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(uid, banned_by, reason, timestamp)| BotBan {
+                user_id: uid.parse().unwrap_or(0),
+                banned_by: banned_by.parse().unwrap_or(0),
+                reason,
+                timestamp,
+            })
+            .collect())
+    }
+
+    // Warning operations
+    /// Record a warning, returning its ID
+    pub async fn add_warning(&self, warning: &Warning) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO warnings (guild_id, user_id, moderator_id, reason, timestamp) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(warning.guild_id.to_string())
+        .bind(warning.user_id.to_string())
+        .bind(warning.moderator_id.to_string())
+        .bind(&warning.reason)
+        .bind(warning.timestamp)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn get_warnings(&self, guild_id: u64, user_id: u64) -> Result<Vec<Warning>> {
+        let results: Vec<(i64, String, Option<String>, i64)> = sqlx::query_as(
+            "SELECT id, moderator_id, reason, timestamp FROM warnings WHERE guild_id = ? AND user_id = ? ORDER BY timestamp DESC",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(id, moderator_id, reason, timestamp)| Warning {
+                id,
+                guild_id,
+                user_id,
+                moderator_id: moderator_id.parse().unwrap_or(0),
+                reason,
+                timestamp,
+            })
+            .collect())
+    }
+
+    pub async fn get_warning(&self, guild_id: u64, warning_id: i64) -> Result<Option<Warning>> {
+        let result: Option<(String, String, Option<String>, i64)> = sqlx::query_as(
+            "SELECT user_id, moderator_id, reason, timestamp FROM warnings WHERE guild_id = ? AND id = ?",
+        )
+        .bind(guild_id.to_string())
+        .bind(warning_id)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(result.map(|(time_between, warning, remaining)| CleanConfig {
+        Ok(result.map(|(user_id, moderator_id, reason, timestamp)| Warning {
+            id: warning_id,
             guild_id,
-            channel_name: channel_name.to_string(),
-            time_between_cleans: time_between,
-            warning_time: warning,
-            remaining_time: remaining,
+            user_id: user_id.parse().unwrap_or(0),
+            moderator_id: moderator_id.parse().unwrap_or(0),
+            reason,
+            timestamp,
         }))
     }
 
-    pub async fn set_clean(
-        &self,
-        guild_id: u64,
-        channel_name: &str,
-        time_between: i64,
-        warning_time: i64,
-        remaining_time: Option<i64>,
-    ) -> Result<()> {
-        let remaining = remaining_time.unwrap_or(time_between * 60);
+    pub async fn count_warnings(&self, guild_id: u64, user_id: u64) -> Result<i64> {
+        let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM warnings WHERE guild_id = ? AND user_id = ?")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(result.0)
+    }
+
+    pub async fn delete_warning(&self, guild_id: u64, warning_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM warnings WHERE guild_id = ? AND id = ?")
+            .bind(guild_id.to_string())
+            .bind(warning_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete all of a user's warnings, returning how many were removed
+    pub async fn clear_warnings(&self, guild_id: u64, user_id: u64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM warnings WHERE guild_id = ? AND user_id = ?")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // Temporary ban operations
+    pub async fn set_temp_ban(&self, ban: &TempBan) -> Result<()> {
         sqlx::query(
-            r#"
-            INSERT INTO channel_cleans(guild_id, channel_name, time_between_cleans, warning_time, remaining_time)
-            VALUES(?, ?, ?, ?, ?)
-            ON CONFLICT(guild_id, channel_name) DO UPDATE SET
-                time_between_cleans = ?, warning_time = ?, remaining_time = ?
-            "#,
+            "INSERT OR REPLACE INTO temp_bans (guild_id, user_id, moderator_id, expires_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(ban.guild_id.to_string())
+        .bind(ban.user_id.to_string())
+        .bind(ban.moderator_id.to_string())
+        .bind(ban.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove a pending temporary ban, returning whether there was one
+    pub async fn remove_temp_ban(&self, guild_id: u64, user_id: u64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM temp_bans WHERE guild_id = ? AND user_id = ?")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_temp_ban(&self, guild_id: u64, user_id: u64) -> Result<Option<TempBan>> {
+        let result: Option<(String, i64)> = sqlx::query_as(
+            "SELECT moderator_id, expires_at FROM temp_bans WHERE guild_id = ? AND user_id = ?",
         )
         .bind(guild_id.to_string())
-        .bind(channel_name.to_lowercase())
-        .bind(time_between)
-        .bind(warning_time)
-        .bind(remaining)
-        .bind(time_between)
-        .bind(warning_time)
-        .bind(remaining)
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.map(|(moderator_id, expires_at)| TempBan {
+            guild_id,
+            user_id,
+            moderator_id: moderator_id.parse().unwrap_or(0),
+            expires_at,
+        }))
+    }
+
+    pub async fn get_expired_temp_bans(&self, now: i64) -> Result<Vec<TempBan>> {
+        let results: Vec<(String, String, String, i64)> = sqlx::query_as(
+            "SELECT guild_id, user_id, moderator_id, expires_at FROM temp_bans WHERE expires_at <= ? ORDER BY expires_at",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(guild_id, user_id, moderator_id, expires_at)| TempBan {
+                guild_id: guild_id.parse().unwrap_or(0),
+                user_id: user_id.parse().unwrap_or(0),
+                moderator_id: moderator_id.parse().unwrap_or(0),
+                expires_at,
+            })
+            .collect())
+    }
+
+    // Ban appeal operations
+    /// Record a pending appeal, returning its ID
+    pub async fn add_ban_appeal(&self, appeal: &BanAppeal) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO ban_appeals (guild_id, user_id, case_number, content, status, created_at) VALUES (?, ?, ?, ?, 'pending', ?)",
+        )
+        .bind(appeal.guild_id.to_string())
+        .bind(appeal.user_id.to_string())
+        .bind(appeal.case_number)
+        .bind(&appeal.content)
+        .bind(appeal.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn get_ban_appeal(&self, appeal_id: i64) -> Result<Option<BanAppeal>> {
+        let result: Option<BanAppealRow> = sqlx::query_as(
+            "SELECT id, guild_id, user_id, case_number, content, status, reviewer_id, created_at, reviewed_at FROM ban_appeals WHERE id = ?",
+        )
+        .bind(appeal_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.map(
+            |(id, guild_id, user_id, case_number, content, status, reviewer_id, created_at, reviewed_at)| BanAppeal {
+                id,
+                guild_id: guild_id.parse().unwrap_or(0),
+                user_id: user_id.parse().unwrap_or(0),
+                case_number,
+                content,
+                status,
+                reviewer_id: reviewer_id.and_then(|r| r.parse().ok()),
+                created_at,
+                reviewed_at,
+            },
+        ))
+    }
+
+    pub async fn has_pending_ban_appeal(&self, guild_id: u64, user_id: u64) -> Result<bool> {
+        let result: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM ban_appeals WHERE guild_id = ? AND user_id = ? AND status = 'pending'",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.0 > 0)
+    }
+
+    /// Mark a pending appeal as accepted or denied, returning false if it was already reviewed
+    pub async fn resolve_ban_appeal(&self, appeal_id: i64, status: &str, reviewer_id: u64) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE ban_appeals SET status = ?, reviewer_id = ?, reviewed_at = ? WHERE id = ? AND status = 'pending'",
+        )
+        .bind(status)
+        .bind(reviewer_id.to_string())
+        .bind(chrono::Utc::now().timestamp())
+        .bind(appeal_id)
         .execute(&self.pool)
         .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Moderator note operations
+    /// Add a note about a user, returning its ID
+    pub async fn add_user_note(&self, note: &UserNote) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO user_notes (guild_id, user_id, author_id, content, timestamp) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(note.guild_id.to_string())
+        .bind(note.user_id.to_string())
+        .bind(note.author_id.to_string())
+        .bind(&note.content)
+        .bind(note.timestamp)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn get_user_notes(&self, guild_id: u64, user_id: u64) -> Result<Vec<UserNote>> {
+        let results: Vec<(i64, String, String, i64)> = sqlx::query_as(
+            "SELECT id, author_id, content, timestamp FROM user_notes WHERE guild_id = ? AND user_id = ? ORDER BY timestamp DESC",
+        )
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(id, author_id, content, timestamp)| UserNote {
+                id,
+                guild_id,
+                user_id,
+                author_id: author_id.parse().unwrap_or(0),
+                content,
+                timestamp,
+            })
+            .collect())
+    }
+
+    pub async fn count_user_notes(&self, guild_id: u64, user_id: u64) -> Result<i64> {
+        let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM user_notes WHERE guild_id = ? AND user_id = ?")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(result.0)
+    }
+
+    /// Delete a note, returning the user it was about if it existed
+    pub async fn remove_user_note(&self, guild_id: u64, note_id: i64) -> Result<Option<u64>> {
+        let result: Option<(String,)> =
+            sqlx::query_as("DELETE FROM user_notes WHERE guild_id = ? AND id = ? RETURNING user_id")
+                .bind(guild_id.to_string())
+                .bind(note_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(result.map(|(user_id,)| user_id.parse().unwrap_or(0)))
+    }
+
+    // Escalation ladder operations
+    pub async fn get_escalation_steps(&self, guild_id: u64) -> Result<Vec<EscalationStep>> {
+        let results: Vec<(i64, String, Option<i64>)> = sqlx::query_as(
+            "SELECT warn_count, action, duration_minutes FROM escalation_steps WHERE guild_id = ? ORDER BY warn_count",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(warn_count, action, duration_minutes)| EscalationStep {
+                guild_id,
+                warn_count,
+                action,
+                duration_minutes,
+            })
+            .collect())
+    }
+
+    pub async fn set_escalation_step(&self, step: &EscalationStep) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO escalation_steps (guild_id, warn_count, action, duration_minutes) VALUES (?, ?, ?, ?)",
+        )
+        .bind(step.guild_id.to_string())
+        .bind(step.warn_count)
+        .bind(&step.action)
+        .bind(step.duration_minutes)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove an escalation step, returning whether it existed
+    pub async fn remove_escalation_step(&self, guild_id: u64, warn_count: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM escalation_steps WHERE guild_id = ? AND warn_count = ?")
+            .bind(guild_id.to_string())
+            .bind(warn_count)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Warning count of the last escalation step applied to a user, 0 if none
+    pub async fn get_applied_escalation(&self, guild_id: u64, user_id: u64) -> Result<i64> {
+        let result: Option<(i64,)> =
+            sqlx::query_as("SELECT warn_count FROM escalation_applied WHERE guild_id = ? AND user_id = ?")
+                .bind(guild_id.to_string())
+                .bind(user_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(result.map(|(count,)| count).unwrap_or(0))
+    }
+
+    pub async fn set_applied_escalation(&self, guild_id: u64, user_id: u64, warn_count: i64) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO escalation_applied (guild_id, user_id, warn_count) VALUES (?, ?, ?)")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .bind(warn_count)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
-    pub async fn delete_clean(&self, guild_id: u64, channel_name: &str) -> Result<()> {
-        sqlx::query("DELETE FROM channel_cleans WHERE guild_id = ? AND channel_name = ?")
+    // Spam warning operations
+    pub async fn add_spam_warning(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        reason: &str,
+        timestamp: i64,
+    ) -> Result<()> {
+        sqlx::query("INSERT INTO spam_warnings (guild_id, user_id, reason, timestamp) VALUES (?, ?, ?, ?)")
             .bind(guild_id.to_string())
-            .bind(channel_name.to_lowercase())
+            .bind(user_id.to_string())
+            .bind(reason)
+            .bind(timestamp)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
-    pub async fn get_all_cleans(&self) -> Result<Vec<CleanConfig>> {
-        let results: Vec<(String, String, i64, i64, i64)> = sqlx::query_as(
-            "SELECT guild_id, channel_name, time_between_cleans, warning_time, remaining_time FROM channel_cleans",
+    /// Count a user's spam warnings in a guild issued at or after `since`
+    pub async fn count_spam_warnings(&self, guild_id: u64, user_id: u64, since: i64) -> Result<i64> {
+        let result: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM spam_warnings WHERE guild_id = ? AND user_id = ? AND timestamp >= ?",
         )
-        .fetch_all(&self.pool)
+        .bind(guild_id.to_string())
+        .bind(user_id.to_string())
+        .bind(since)
+        .fetch_one(&self.pool)
         .await?;
-
-        Ok(results
-            .into_iter()
-            .map(|(gid, cname, time_between, warning, remaining)| CleanConfig {
-                guild_id: gid.parse().unwrap_or(0),
-                channel_name: cname,
-                time_between_cleans: time_between,
-                warning_time: warning,
-                remaining_time: remaining,
-            })
-            .collect())
+        Ok(result.0)
     }
 
-    // XP Batching operations
-    pub async fn add_xp_to_batch(&self, user_id: u64, guild_id: u64, channel_id: u64, xp: i64) {
-        let key = format!("{}:{}", guild_id, user_id);
-        let mut pending = self.pending_xp.lock().await;
-
-        if let Some(entry) = pending.get_mut(&key) {
-            entry.xp_amount += xp;
-            entry.channel_id = channel_id;
-        } else {
-            pending.insert(
-                key,
-                PendingXp {
-                    user_id,
-                    guild_id,
-                    channel_id,
-                    xp_amount: xp,
-                    added_at: chrono::Utc::now().timestamp(),
-                },
-            );
-        }
+    /// Delete a user's spam warnings in a guild issued before `before`
+    pub async fn prune_spam_warnings(&self, guild_id: u64, user_id: u64, before: i64) -> Result<()> {
+        sqlx::query("DELETE FROM spam_warnings WHERE guild_id = ? AND user_id = ? AND timestamp < ?")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
     }
 
-    pub async fn flush_xp_batch(&self) -> Result<Vec<(u64, u64, u64, i64, i64)>> {
-        let mut pending = self.pending_xp.lock().await;
-        if pending.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let to_process: HashMap<String, PendingXp> = std::mem::take(&mut *pending);
-        drop(pending);
-
-        let mut level_ups = Vec::new();
-
-        for (_, xp_entry) in to_process {
-            let (current_xp, current_level) = self.get_xp(xp_entry.guild_id, xp_entry.user_id).await?;
-            let new_xp = current_xp + xp_entry.xp_amount;
-            let new_level = ((new_xp as f64) / 100.0).sqrt() as i64;
-
-            self.set_xp(xp_entry.guild_id, xp_entry.user_id, new_xp, new_level).await?;
-
-            if new_level > current_level {
-                level_ups.push((xp_entry.user_id, xp_entry.guild_id, xp_entry.channel_id, new_level, new_xp));
-            }
-        }
-
-        Ok(level_ups)
+    pub async fn clear_spam_warnings(&self, guild_id: u64, user_id: u64) -> Result<()> {
+        sqlx::query("DELETE FROM spam_warnings WHERE guild_id = ? AND user_id = ?")
+            .bind(guild_id.to_string())
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
     }
 
-    // Voice XP config operations
-    pub async fn get_voice_xp_config(&self, guild_id: u64) -> Result<VoiceXpConfig> {
-        let result: Option<(i32, i32, i32, i32)> = sqlx::query_as(
-            "SELECT enabled, xp_per_minute, min_users, ignore_afk FROM voice_xp_config WHERE guild_id = ?",
+    // Spam filter rule operations
+    pub async fn get_spam_rules(&self, guild_id: u64) -> Result<Vec<SpamRuleConfig>> {
+        let results: Vec<(String, i32, String, i64, i64)> = sqlx::query_as(
+            "SELECT rule, enabled, action, threshold, timeout_minutes FROM spam_filter_rules WHERE guild_id = ?",
         )
         .bind(guild_id.to_string())
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(result
-            .map(|(enabled, xp_per_min, min_users, ignore_afk)| VoiceXpConfig {
+        Ok(results
+            .into_iter()
+            .map(|(rule, enabled, action, threshold, timeout_minutes)| SpamRuleConfig {
                 guild_id,
+                rule,
                 enabled: enabled != 0,
-                xp_per_minute: xp_per_min,
-                min_users,
-                ignore_afk: ignore_afk != 0,
+                action,
+                threshold,
+                timeout_minutes,
             })
-            .unwrap_or(VoiceXpConfig {
-                guild_id,
-                enabled: false,
-                xp_per_minute: 5,
-                min_users: 2,
-                ignore_afk: true,
-            }))
+            .collect())
     }
 
-    pub async fn set_voice_xp_config(&self, config: &VoiceXpConfig) -> Result<()> {
+    pub async fn set_spam_rule(&self, config: &SpamRuleConfig) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO voice_xp_config (guild_id, enabled, xp_per_minute, min_users, ignore_afk)
-            VALUES (?, ?, ?, ?, ?)
-            ON CONFLICT(guild_id) DO UPDATE SET
-                enabled = ?, xp_per_minute = ?, min_users = ?, ignore_afk = ?
+            INSERT INTO spam_filter_rules (guild_id, rule, enabled, action, threshold, timeout_minutes)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(guild_id, rule) DO UPDATE SET
+                enabled = ?, action = ?, threshold = ?, timeout_minutes = ?
             "#,
         )
         .bind(config.guild_id.to_string())
+        .bind(&config.rule)
         .bind(config.enabled as i32)
-        .bind(config.xp_per_minute)
-        .bind(config.min_users)
-        .bind(config.ignore_afk as i32)
+        .bind(&config.action)
+        .bind(config.threshold)
+        .bind(config.timeout_minutes)
         .bind(config.enabled as i32)
-        .bind(config.xp_per_minute)
-        .bind(config.min_users)
-        .bind(config.ignore_afk as i32)
+        .bind(&config.action)
+        .bind(config.threshold)
+        .bind(config.timeout_minutes)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    // Activity log operations
-    pub async fn log_activity(&self, log: &ActivityLog) -> Result<()> {
-        sqlx::query(
+    pub async fn delete_spam_rule(&self, guild_id: u64, rule: &str) -> Result<()> {
+        sqlx::query("DELETE FROM spam_filter_rules WHERE guild_id = ? AND rule = ?")
+            .bind(guild_id.to_string())
+            .bind(rule)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Spam filter threshold operations
+    pub async fn get_spam_filter_settings(&self, guild_id: u64) -> Result<SpamFilterSettings> {
+        let result: Option<SpamFilterSettingsRow> = sqlx::query_as(
             r#"
-            INSERT INTO activity_log (guild_id, user_id, channel_id, event_type, old_content, new_content, timestamp)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            SELECT flood_messages, flood_seconds, duplicate_count, duplicate_seconds, duplicate_similarity,
+                mention_limit, caps_percent, caps_min_length, zalgo_percent, emoji_limit, newline_limit,
+                observe_mode, report_channel_id
+            FROM spam_filter_settings WHERE guild_id = ?
             "#,
         )
-        .bind(log.guild_id.to_string())
-        .bind(log.user_id.to_string())
-        .bind(log.channel_id.to_string())
-        .bind(&log.event_type)
-        .bind(&log.old_content)
-        .bind(&log.new_content)
-        .bind(log.timestamp)
-        .execute(&self.pool)
+        .bind(guild_id.to_string())
+        .fetch_optional(&self.pool)
         .await?;
-        Ok(())
-    }
-
-    pub async fn get_activity_logs(&self, guild_id: u64, limit: i64) -> Result<Vec<ActivityLog>> {
-        let results: Vec<(i64, String, String, String, Option<String>, Option<String>, i64)> =
-            sqlx::query_as(
-                "SELECT id, user_id, channel_id, event_type, old_content, new_content, timestamp FROM activity_log WHERE guild_id = ? ORDER BY timestamp DESC LIMIT ?",
-            )
-            .bind(guild_id.to_string())
-            .bind(limit)
-            .fetch_all(&self.pool)
-            .await?;
 
-        Ok(results
-            .into_iter()
-            .map(|(id, uid, cid, event_type, old_content, new_content, timestamp)| ActivityLog {
-                id,
+        Ok(result
+            .map(|row| SpamFilterSettings {
                 guild_id,
-                user_id: uid.parse().unwrap_or(0),
-                channel_id: cid.parse().unwrap_or(0),
-                event_type,
-                old_content,
-                new_content,
-                timestamp,
+                flood_messages: row.0,
+                flood_seconds: row.1,
+                duplicate_count: row.2,
+                duplicate_seconds: row.3,
+                duplicate_similarity: row.4,
+                mention_limit: row.5,
+                caps_percent: row.6,
+                caps_min_length: row.7,
+                zalgo_percent: row.8,
+                emoji_limit: row.9,
+                newline_limit: row.10,
+                observe_mode: row.11 != 0,
+                report_channel_id: row.12.and_then(|id| id.parse().ok()),
             })
-            .collect())
+            .unwrap_or_else(|| SpamFilterSettings::default_for(guild_id)))
     }
 
-    // DM inbox operations
-    pub async fn save_dm(&self, dm: &DmInbox) -> Result<()> {
+    pub async fn set_spam_filter_settings(&self, settings: &SpamFilterSettings) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO dm_inbox (user_id, username, content, timestamp, read_status)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT OR REPLACE INTO spam_filter_settings
+                (guild_id, flood_messages, flood_seconds, duplicate_count, duplicate_seconds, duplicate_similarity,
+                mention_limit, caps_percent, caps_min_length, zalgo_percent, emoji_limit, newline_limit,
+                observe_mode, report_channel_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
-        .bind(dm.user_id.to_string())
-        .bind(&dm.username)
-        .bind(&dm.content)
-        .bind(dm.timestamp)
-        .bind(dm.read_status as i32)
+        .bind(settings.guild_id.to_string())
+        .bind(settings.flood_messages)
+        .bind(settings.flood_seconds)
+        .bind(settings.duplicate_count)
+        .bind(settings.duplicate_seconds)
+        .bind(settings.duplicate_similarity)
+        .bind(settings.mention_limit)
+        .bind(settings.caps_percent)
+        .bind(settings.caps_min_length)
+        .bind(settings.zalgo_percent)
+        .bind(settings.emoji_limit)
+        .bind(settings.newline_limit)
+        .bind(settings.observe_mode as i32)
+        .bind(settings.report_channel_id.map(|id| id.to_string()))
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    pub async fn get_dms(&self, limit: i64) -> Result<Vec<DmInbox>> {
-        let results: Vec<(i64, String, Option<String>, Option<String>, i64, i32)> = sqlx::query_as(
-            "SELECT id, user_id, username, content, timestamp, read_status FROM dm_inbox ORDER BY timestamp DESC LIMIT ?",
+    // Spam filter exemption operations
+    pub async fn get_spam_exemptions(&self, guild_id: u64) -> Result<Vec<SpamExemption>> {
+        let results: Vec<(String, i32, String)> = sqlx::query_as(
+            "SELECT target_id, is_channel, rule FROM spam_filter_exemptions WHERE guild_id = ?",
         )
-        .bind(limit)
+        .bind(guild_id.to_string())
         .fetch_all(&self.pool)
         .await?;
 
         Ok(results
             .into_iter()
-            .map(|(id, uid, username, content, timestamp, read_status)| DmInbox {
-                id,
-                user_id: uid.parse().unwrap_or(0),
-                username: username.unwrap_or_default(),
-                content: content.unwrap_or_default(),
-                timestamp,
-                read_status: read_status != 0,
+            .map(|(target_id, is_channel, rule)| SpamExemption {
+                guild_id,
+                target_id: target_id.parse().unwrap_or(0),
+                is_channel: is_channel != 0,
+                rule,
             })
             .collect())
     }
 
-    pub async fn mark_dm_read(&self, dm_id: i64) -> Result<()> {
-        sqlx::query("UPDATE dm_inbox SET read_status = 1 WHERE id = ?")
-            .bind(dm_id)
-            .execute(&self.pool)
-            .await?;
+    pub async fn add_spam_exemption(&self, exemption: &SpamExemption) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO spam_filter_exemptions (guild_id, target_id, is_channel, rule) VALUES (?, ?, ?, ?)",
+        )
+        .bind(exemption.guild_id.to_string())
+        .bind(exemption.target_id.to_string())
+        .bind(exemption.is_channel as i32)
+        .bind(&exemption.rule)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
-    pub async fn get_unread_dm_count(&self) -> Result<i64> {
-        let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dm_inbox WHERE read_status = 0")
-            .fetch_one(&self.pool)
+    /// Remove an exemption, returning whether it existed
+    pub async fn remove_spam_exemption(&self, guild_id: u64, target_id: u64, rule: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM spam_filter_exemptions WHERE guild_id = ? AND target_id = ? AND rule = ?")
+            .bind(guild_id.to_string())
+            .bind(target_id.to_string())
+            .bind(rule)
+            .execute(&self.pool)
             .await?;
-        Ok(result.0)
+        Ok(result.rows_affected() > 0)
     }
 
-    // Bot ban operations
-    pub async fn add_bot_ban(&self, ban: &BotBan) -> Result<()> {
+    // Spam filter link list operations
+    pub async fn get_link_domains(&self, guild_id: u64) -> Result<Vec<LinkDomain>> {
+        let results: Vec<(String, i32)> = sqlx::query_as(
+            "SELECT domain, blocked FROM spam_link_domains WHERE guild_id = ? ORDER BY domain",
+        )
+        .bind(guild_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(domain, blocked)| LinkDomain {
+                guild_id,
+                domain,
+                blocked: blocked != 0,
+            })
+            .collect())
+    }
+
+    pub async fn set_link_domain(&self, entry: &LinkDomain) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT OR REPLACE INTO bot_bans (user_id, banned_by, reason, timestamp)
-            VALUES (?, ?, ?, ?)
+            INSERT INTO spam_link_domains (guild_id, domain, blocked) VALUES (?, ?, ?)
+            ON CONFLICT(guild_id, domain) DO UPDATE SET blocked = ?
             "#,
         )
-        .bind(ban.user_id.to_string())
-        .bind(ban.banned_by.to_string())
-        .bind(&ban.reason)
-        .bind(ban.timestamp)
+        .bind(entry.guild_id.to_string())
+        .bind(&entry.domain)
+        .bind(entry.blocked as i32)
+        .bind(entry.blocked as i32)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    pub async fn remove_bot_ban(&self, user_id: u64) -> Result<()> {
-        sqlx::query("DELETE FROM bot_bans WHERE user_id = ?")
-            .bind(user_id.to_string())
+    /// Remove a domain from the allow/block lists, returning whether it was listed
+    pub async fn remove_link_domain(&self, guild_id: u64, domain: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM spam_link_domains WHERE guild_id = ? AND domain = ?")
+            .bind(guild_id.to_string())
+            .bind(domain)
             .execute(&self.pool)
             .await?;
-        Ok(())
-    }
-
-    pub async fn is_bot_banned(&self, user_id: u64) -> Result<bool> {
-        let result: Option<(i64,)> = sqlx::query_as("SELECT 1 FROM bot_bans WHERE user_id = ?")
-            .bind(user_id.to_string())
-            .fetch_optional(&self.pool)
-            .await?;
-        Ok(result.is_some())
+        Ok(result.rows_affected() > 0)
     }
 
-    pub async fn get_bot_bans(&self, limit: i64) -> Result<Vec<BotBan>> {
-        let results: Vec<(String, String, Option<String>, i64)> = sqlx::query_as(
-            "SELECT user_id, banned_by, reason, timestamp FROM bot_bans ORDER BY timestamp DESC LIMIT ?",
+    pub async fn get_invite_allowlist(&self, guild_id: u64) -> Result<Vec<u64>> {
+        let results: Vec<(String,)> = sqlx::query_as(
+            "SELECT partner_guild_id FROM spam_invite_allowlist WHERE guild_id = ?",
         )
-        .bind(limit)
+        .bind(guild_id.to_string())
         .fetch_all(&self.pool)
         .await?;
 
         Ok(results
             .into_iter()
-            .map(|(uid, banned_by, reason, timestamp)| BotBan {
-                user_id: uid.parse().unwrap_or(0),
-                banned_by: banned_by.parse().unwrap_or(0),
-                reason,
-                timestamp,
-            })
+            .filter_map(|(partner,)| partner.parse().ok())
             .collect())
     }
+
+    pub async fn add_invite_allow(&self, guild_id: u64, partner_guild_id: u64) -> Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO spam_invite_allowlist (guild_id, partner_guild_id) VALUES (?, ?)")
+            .bind(guild_id.to_string())
+            .bind(partner_guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove a partner server from the invite allowlist, returning whether it was listed
+    pub async fn remove_invite_allow(&self, guild_id: u64, partner_guild_id: u64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM spam_invite_allowlist WHERE guild_id = ? AND partner_guild_id = ?")
+            .bind(guild_id.to_string())
+            .bind(partner_guild_id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -755,5 +2225,37 @@ pub struct ModStats {
     pub action_counts: Vec<(String, i64)>,
     pub top_mods: Vec<(String, i64)>,
     pub mod_counts: Vec<(String, String, i64)>,
+    // (YYYY-MM-DD of the week's Monday or YYYY-MM, count), oldest first
+    pub trend: Vec<(String, i64)>,
+    // (YYYY-MM-DD, action, count), oldest first
+    pub daily: Vec<(String, String, i64)>,
     pub total: i64,
 }
+
+/// Narrows down which actions `get_mod_stats` counts
+#[derive(Debug, Clone, Default)]
+pub struct ModStatsFilter {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub moderator_id: Option<u64>,
+    // Group the trend by month instead of by week
+    pub monthly: bool,
+}
+
+const MOD_STATS_FILTER: &str =
+    "guild_id = ? AND timestamp >= ? AND timestamp <= ? AND (? IS NULL OR moderator_id = ?)";
+
+/// Bind the parameters of `MOD_STATS_FILTER`
+fn bind_stats_filter<'q, O>(
+    query: sqlx::query::QueryAs<'q, Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+    guild_id: u64,
+    filter: &ModStatsFilter,
+) -> sqlx::query::QueryAs<'q, Sqlite, O, sqlx::sqlite::SqliteArguments<'q>> {
+    let moderator_id = filter.moderator_id.map(|id| id.to_string());
+    query
+        .bind(guild_id.to_string())
+        .bind(filter.since.unwrap_or(0))
+        .bind(filter.until.unwrap_or(i64::MAX))
+        .bind(moderator_id.clone())
+        .bind(moderator_id)
+}
@@ -0,0 +1,260 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::temp_bans;
+use crate::database::{BanAppeal, Database};
+use crate::utils::truncate;
+use crate::Data;
+use poise::serenity_prelude as serenity;
+
+const MAX_APPEAL_LENGTH: usize = 1500;
+const ACCEPT_PREFIX: &str = "appeal-accept:";
+const DENY_PREFIX: &str = "appeal-deny:";
+
+/// How to appeal a ban from this guild, if it takes appeals
+pub async fn appeal_instructions(
+    db: &Database,
+    guild_id: serenity::GuildId,
+) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(db.get_appeal_channel(guild_id.get()).await?.map(|_| {
+        format!(
+            "To appeal, reply to this message with `appeal {} <why you should be unbanned>`",
+            guild_id
+        )
+    }))
+}
+
+/// Handle an `appeal <server ID> <text>` DM, returning false if the message isn't one
+pub async fn handle_dm(
+    ctx: &serenity::Context,
+    data: &Data,
+    message: &serenity::Message,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let content = message.content.trim();
+    let (command, rest) = content.split_once(char::is_whitespace).unwrap_or((content, ""));
+    if !command.eq_ignore_ascii_case("appeal") {
+        return Ok(false);
+    }
+
+    let reply = |text: String| message.channel_id.say(ctx, text);
+
+    let rest = rest.trim_start();
+    let (guild_id, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let text = text.trim();
+    let Some(guild_id) = guild_id.parse::<u64>().ok().filter(|id| *id != 0 && !text.is_empty()) else {
+        reply("Usage: `appeal <server ID> <why you should be unbanned>`".to_string()).await?;
+        return Ok(true);
+    };
+    if text.chars().count() > MAX_APPEAL_LENGTH {
+        reply(format!(":x: Appeals can be at most {} characters", MAX_APPEAL_LENGTH)).await?;
+        return Ok(true);
+    }
+
+    let guild_id = serenity::GuildId::new(guild_id);
+    let user_id = message.author.id;
+
+    let Some(channel_id) = data.db.get_appeal_channel(guild_id.get()).await? else {
+        reply(":x: That server doesn't take ban appeals".to_string()).await?;
+        return Ok(true);
+    };
+    if !matches!(ctx.http.get_ban(guild_id, user_id).await, Ok(Some(_))) {
+        reply(":x: You aren't banned from that server".to_string()).await?;
+        return Ok(true);
+    }
+    if data.db.has_pending_ban_appeal(guild_id.get(), user_id.get()).await? {
+        reply(":x: You already have an appeal waiting for review in that server".to_string()).await?;
+        return Ok(true);
+    }
+
+    // Tie the appeal to the most recent ban we have on record
+    let ban_case = data
+        .db
+        .get_user_mod_actions(guild_id.get(), user_id.get())
+        .await?
+        .into_iter()
+        .find(|case| matches!(case.action.as_str(), "ban" | "tempban"));
+
+    let appeal_id = data
+        .db
+        .add_ban_appeal(&BanAppeal {
+            id: 0,
+            guild_id: guild_id.get(),
+            user_id: user_id.get(),
+            case_number: ban_case.as_ref().map(|c| c.case_number),
+            content: text.to_string(),
+            status: "pending".to_string(),
+            reviewer_id: None,
+            created_at: chrono::Utc::now().timestamp(),
+            reviewed_at: None,
+        })
+        .await?;
+
+    let case = match &ban_case {
+        Some(case) => format!(
+            "#{} - {}",
+            case.case_number,
+            truncate(case.reason.as_deref().unwrap_or("No reason given"), 200)
+        ),
+        None => "Not on record".to_string(),
+    };
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("Ban appeal #{}", appeal_id))
+        .color(0xff69b4)
+        .field("User", format!("<@{}> ({})", user_id, user_id), true)
+        .field("Case", case, true)
+        .field("Appeal", truncate(text, 1000), false)
+        .timestamp(serenity::Timestamp::now());
+    let buttons = serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!("{}{}", ACCEPT_PREFIX, appeal_id))
+            .label("Accept")
+            .style(serenity::ButtonStyle::Success),
+        serenity::CreateButton::new(format!("{}{}", DENY_PREFIX, appeal_id))
+            .label("Deny")
+            .style(serenity::ButtonStyle::Danger),
+    ]);
+
+    if let Err(e) = serenity::ChannelId::new(channel_id)
+        .send_message(ctx, serenity::CreateMessage::new().embed(embed).components(vec![buttons]))
+        .await
+    {
+        tracing::warn!("Failed to post appeal {} in guild {}: {}", appeal_id, guild_id, e);
+        // Don't leave an appeal nobody can see blocking the next one
+        data.db.resolve_ban_appeal(appeal_id, "undelivered", 0).await?;
+        reply(":x: Your appeal couldn't be delivered, please try again later".to_string()).await?;
+        return Ok(true);
+    }
+
+    reply(":white_check_mark: Your appeal has been sent to the server's staff".to_string()).await?;
+
+    Ok(true)
+}
+
+/// Handle the Accept and Deny buttons on an appeal
+pub async fn handle_component(
+    ctx: &serenity::Context,
+    data: &Data,
+    press: &serenity::ComponentInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let custom_id = press.data.custom_id.as_str();
+    let (accept, appeal_id) = if let Some(id) = custom_id.strip_prefix(ACCEPT_PREFIX) {
+        (true, id)
+    } else if let Some(id) = custom_id.strip_prefix(DENY_PREFIX) {
+        (false, id)
+    } else {
+        return Ok(());
+    };
+    let Ok(appeal_id) = appeal_id.parse::<i64>() else {
+        return Ok(());
+    };
+
+    let respond = |text: &str| {
+        press.create_response(
+            ctx,
+            serenity::CreateInteractionResponse::Message(
+                serenity::CreateInteractionResponseMessage::new()
+                    .content(text)
+                    .ephemeral(true),
+            ),
+        )
+    };
+
+    let can_ban = press
+        .member
+        .as_ref()
+        .and_then(|m| m.permissions)
+        .is_some_and(|p| p.ban_members());
+    if !can_ban {
+        respond(":x: You need the Ban Members permission to review appeals").await?;
+        return Ok(());
+    }
+
+    let Some(appeal) = data.db.get_ban_appeal(appeal_id).await? else {
+        respond(":x: That appeal no longer exists").await?;
+        return Ok(());
+    };
+    if press.guild_id.map(|g| g.get()) != Some(appeal.guild_id) {
+        respond(":x: That appeal belongs to a different server").await?;
+        return Ok(());
+    }
+    if appeal.status != "pending" {
+        respond(&format!(":x: That appeal was already {}", appeal.status)).await?;
+        return Ok(());
+    }
+
+    let guild_id = serenity::GuildId::new(appeal.guild_id);
+    let user_id = serenity::UserId::new(appeal.user_id);
+    let reviewer_id = press.user.id;
+
+    let outcome = if accept {
+        let reason = format!("Ban appeal #{} accepted", appeal.id);
+        match temp_bans::lift_ban(&ctx.http, &data.db, guild_id, user_id, reviewer_id.get(), Some(&reason)).await {
+            Ok(case_number) => format!("Accepted by <@{}> | Case #{}", reviewer_id, case_number),
+            Err(e) => {
+                respond(&format!(":x: Failed to unban: {}", e)).await?;
+                return Ok(());
+            }
+        }
+    } else {
+        format!("Denied by <@{}>", reviewer_id)
+    };
+
+    let status = if accept { "accepted" } else { "denied" };
+    if !data.db.resolve_ban_appeal(appeal.id, status, reviewer_id.get()).await? {
+        respond(":x: That appeal was already reviewed").await?;
+        return Ok(());
+    }
+
+    // Keep the appeal in the channel, minus the buttons
+    let embed = press
+        .message
+        .embeds
+        .first()
+        .cloned()
+        .map(serenity::CreateEmbed::from)
+        .unwrap_or_default()
+        .color(if accept { 0x00c853 } else { 0xff0000 })
+        .field("Outcome", outcome, false);
+    press
+        .create_response(
+            ctx,
+            serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    let guild_name = ctx
+        .cache
+        .guild(guild_id)
+        .map(|g| g.name.clone())
+        .unwrap_or_else(|| "the server".to_string());
+    let _ = user_id
+        .dm(
+            ctx,
+            serenity::CreateMessage::new().content(format!(
+                "Your ban appeal for **{}** was {}.",
+                guild_name, status
+            )),
+        )
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::appeals;
+use crate::utils::truncate;
+use crate::Data;
+use poise::serenity_prelude as serenity;
+
+/// Used when the guild hasn't set a template of its own
+pub const DEFAULT_TEMPLATE: &str = "You have been banned from **${guild}**.\n**Reason:** ${reason}";
+
+/// Fill in a ban DM template's `${guild}`, `${user}`, `${moderator}` and `${reason}` placeholders
+pub fn render_template(template: &str, guild_name: &str, user: &str, moderator: &str, reason: &str) -> String {
+    template
+        .replace("${guild}", guild_name)
+        .replace("${user}", user)
+        .replace("${moderator}", moderator)
+        .replace("${reason}", reason)
+}
+
+/// DM a user about to be banned, using the guild's template and the moderator's ban image.
+/// Has to happen before the ban, as we lose our shared server after.
+pub async fn notify_ban(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+    moderator: &serenity::User,
+    reason: Option<&str>,
+    expires_at: Option<i64>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let guild_name = ctx
+        .cache
+        .guild(guild_id)
+        .map(|g| g.name.clone())
+        .unwrap_or_else(|| "the server".to_string());
+    let template = data.db.get_ban_dm_template(guild_id.get()).await?;
+    let description = render_template(
+        template.as_deref().unwrap_or(DEFAULT_TEMPLATE),
+        &guild_name,
+        &user.name,
+        &moderator.name,
+        reason.unwrap_or("No reason given"),
+    );
+
+    // The moderator's own image, then the guild's, then the global default
+    let image = match data.db.get_ban_image(guild_id.get(), Some(moderator.id.get())).await? {
+        Some(image) => Some(image),
+        None => data.db.get_ban_image(guild_id.get(), None).await?,
+    }
+    .or_else(|| data.config.ban_default_image.clone());
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("You have been banned from {}", guild_name))
+        .color(0xff0000)
+        .description(truncate(&description, 4000));
+
+    if let Some(image) = image {
+        embed = embed.image(image);
+    }
+    if let Some(expires_at) = expires_at {
+        embed = embed.field("Expires", format!("<t:{}:f>", expires_at), false);
+    }
+    if let Some(instructions) = appeals::appeal_instructions(&data.db, guild_id).await? {
+        embed = embed.field("Appeal", instructions, false);
+    }
+
+    // Closed DMs are common, there's nothing to do about them
+    let _ = user.dm(ctx, serenity::CreateMessage::new().embed(embed)).await;
+
+    Ok(())
+}
@@ -0,0 +1,262 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::{appeals, mod_log};
+use crate::database::{EscalationStep, ModAction, Warning};
+use crate::utils::format_duration;
+use crate::Data;
+use poise::serenity_prelude as serenity;
+use poise::ChoiceParameter;
+
+/// Timeout length used when a timeout step has no duration set
+pub const DEFAULT_TIMEOUT_MINUTES: i64 = 60;
+
+/// What happens once a user reaches a step's warning count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum EscalationAction {
+    #[name = "timeout"]
+    Timeout,
+    #[name = "kick"]
+    Kick,
+    #[name = "ban"]
+    Ban,
+}
+
+impl EscalationAction {
+    pub fn past_tense(self) -> &'static str {
+        match self {
+            EscalationAction::Timeout => "timed out",
+            EscalationAction::Kick => "kicked",
+            EscalationAction::Ban => "banned",
+        }
+    }
+}
+
+/// Human readable form of a step's action, e.g. "timeout for 1h"
+pub fn describe_step(step: &EscalationStep) -> String {
+    match EscalationAction::from_name(&step.action) {
+        Some(EscalationAction::Timeout) => format!(
+            "timeout for {}",
+            format_duration(step.duration_minutes.unwrap_or(DEFAULT_TIMEOUT_MINUTES) * 60)
+        ),
+        _ => step.action.clone(),
+    }
+}
+
+/// Record a warning along with its mod action, returning the warning ID and the user's warning count
+pub async fn record_warning(
+    http: &serenity::Http,
+    data: &Data,
+    guild_id: u64,
+    user_id: u64,
+    moderator_id: u64,
+    reason: Option<&str>,
+) -> Result<(i64, i64), Box<dyn std::error::Error + Send + Sync>> {
+    let now = chrono::Utc::now().timestamp();
+
+    let warning_id = data
+        .db
+        .add_warning(&Warning {
+            id: 0,
+            guild_id,
+            user_id,
+            moderator_id,
+            reason: reason.map(str::to_string),
+            timestamp: now,
+        })
+        .await?;
+
+    mod_log::record(
+        http,
+        &data.db,
+        ModAction::new(guild_id, moderator_id, user_id, "warn", reason),
+    )
+    .await?;
+
+    let count = data.db.count_warnings(guild_id, user_id).await?;
+
+    Ok((warning_id, count))
+}
+
+/// Pick the highest step the warning count reaches, unless it was already applied.
+/// Steps are expected in ascending `warn_count` order, as the database returns them.
+pub fn next_step(steps: Vec<EscalationStep>, applied: i64, count: i64) -> Option<EscalationStep> {
+    let step = steps.into_iter().rev().find(|s| s.warn_count <= count)?;
+
+    // Steps above the count before this warning are due again, e.g. after warnings were removed
+    if step.warn_count <= applied.min(count - 1) {
+        return None;
+    }
+
+    Some(step)
+}
+
+/// Apply the highest escalation step the given warning count reaches, unless it was already applied
+pub async fn escalate(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+    count: i64,
+) -> Result<Option<EscalationStep>, Box<dyn std::error::Error + Send + Sync>> {
+    let steps = data.db.get_escalation_steps(guild_id.get()).await?;
+    let applied = data
+        .db
+        .get_applied_escalation(guild_id.get(), user.id.get())
+        .await?;
+    let Some(step) = next_step(steps, applied, count) else {
+        return Ok(None);
+    };
+
+    let Some(action) = EscalationAction::from_name(&step.action) else {
+        tracing::warn!("Unknown escalation action '{}' in guild {}", step.action, guild_id);
+        return Ok(None);
+    };
+
+    let reason = format!("Escalation: reached {} warnings", count);
+    let guild_name = ctx
+        .cache
+        .guild(guild_id)
+        .map(|g| g.name.clone())
+        .unwrap_or_else(|| "the server".to_string());
+
+    match action {
+        EscalationAction::Timeout => {
+            let minutes = step
+                .duration_minutes
+                .unwrap_or(DEFAULT_TIMEOUT_MINUTES)
+                .clamp(1, 40320);
+            let timeout_until = chrono::Utc::now() + chrono::Duration::minutes(minutes);
+            let mut member = match guild_id.member(ctx, user.id).await {
+                Ok(member) => member,
+                // They left before we could time them out, so there's nobody to time out
+                Err(serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(ref response)))
+                    if response.status_code == serenity::StatusCode::NOT_FOUND =>
+                {
+                    tracing::info!(
+                        "Skipping escalation timeout for {} in guild {}, they are no longer a member",
+                        user.id,
+                        guild_id
+                    );
+                    data.db
+                        .set_applied_escalation(guild_id.get(), user.id.get(), step.warn_count)
+                        .await?;
+                    return Ok(None);
+                }
+                Err(e) => return Err(e.into()),
+            };
+            member
+                .disable_communication_until_datetime(ctx, timeout_until.into())
+                .await?;
+        }
+        EscalationAction::Kick => {
+            guild_id.kick_with_reason(ctx, user.id, &reason).await?;
+        }
+        EscalationAction::Ban => {
+            mod_log::expect_ban(guild_id, user.id).await;
+            guild_id.ban_with_reason(ctx, user.id, 0, &reason).await?;
+        }
+    }
+    data.db
+        .set_applied_escalation(guild_id.get(), user.id.get(), step.warn_count)
+        .await?;
+
+    // Only tell the user once the action actually went through
+    let mut description = format!("You reached {} warnings ({}).", count, describe_step(&step));
+    if action == EscalationAction::Ban {
+        if let Some(instructions) = appeals::appeal_instructions(&data.db, guild_id).await? {
+            description.push_str(&format!("\n\n{}", instructions));
+        }
+    }
+    let _ = user
+        .dm(
+            ctx,
+            serenity::CreateMessage::new().embed(
+                serenity::CreateEmbed::new()
+                    .title(format!("You have been {} in {}", action.past_tense(), guild_name))
+                    .description(description)
+                    .color(0xff0000),
+            ),
+        )
+        .await;
+
+    // Record to database
+    let bot_user_id = ctx.cache.current_user().id.get();
+    let duration = match action {
+        EscalationAction::Timeout => Some(step.duration_minutes.unwrap_or(DEFAULT_TIMEOUT_MINUTES) * 60),
+        _ => None,
+    };
+    mod_log::record(
+        &ctx.http,
+        &data.db,
+        ModAction {
+            duration,
+            ..ModAction::new(guild_id.get(), bot_user_id, user.id.get(), action.name(), Some(&reason))
+        },
+    )
+    .await?;
+
+    Ok(Some(step))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(counts: &[i64]) -> Vec<EscalationStep> {
+        counts
+            .iter()
+            .map(|&warn_count| EscalationStep {
+                guild_id: 1,
+                warn_count,
+                action: "timeout".to_string(),
+                duration_minutes: None,
+            })
+            .collect()
+    }
+
+    fn next_count(counts: &[i64], applied: i64, count: i64) -> Option<i64> {
+        next_step(steps(counts), applied, count).map(|s| s.warn_count)
+    }
+
+    #[test]
+    fn next_step_picks_highest_reached() {
+        assert_eq!(next_count(&[3, 5, 7], 0, 2), None);
+        assert_eq!(next_count(&[3, 5, 7], 0, 3), Some(3));
+        assert_eq!(next_count(&[3, 5, 7], 0, 6), Some(5));
+        assert_eq!(next_count(&[3, 5, 7], 0, 10), Some(7));
+        assert_eq!(next_count(&[], 0, 10), None);
+    }
+
+    #[test]
+    fn next_step_skips_applied() {
+        assert_eq!(next_count(&[3, 5], 3, 4), None);
+        assert_eq!(next_count(&[3, 5], 3, 5), Some(5));
+        assert_eq!(next_count(&[3, 5], 5, 6), None);
+    }
+
+    #[test]
+    fn next_step_reapplies_after_removed_warnings() {
+        // Applied at 5, then warnings dropped to 2 and built back up
+        assert_eq!(next_count(&[3, 5], 5, 3), Some(3));
+        assert_eq!(next_count(&[3, 5], 5, 5), Some(5));
+        // Applied at 3, count dropped to 2, the next warning reaches 3 again
+        assert_eq!(next_count(&[3], 3, 3), Some(3));
+    }
+}
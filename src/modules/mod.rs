@@ -17,8 +17,16 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+pub mod appeals;
 mod auto_cleaner;
-mod spam_filter;
+pub mod ban_notice;
+pub mod escalation;
+pub mod mod_export;
+pub mod mod_log;
+mod phishing;
+pub mod spam_filter;
+pub mod stats_chart;
+pub mod temp_bans;
 pub mod terminal;
 
 use crate::database::DmInbox;
@@ -45,6 +53,9 @@ pub async fn event_handler(
             // Start auto-cleaner task
             auto_cleaner::start(ctx.clone(), data.db.clone()).await;
 
+            // Start temp ban scheduler
+            temp_bans::start(ctx.clone(), data.db.clone()).await;
+
             // Start terminal
             terminal::start(ctx.clone(), data.db.clone()).await;
 
@@ -92,12 +103,16 @@ pub async fn event_handler(
                     preview
                 );
 
+                // Ban appeals get their own reply
+                if appeals::handle_dm(ctx, data, new_message).await? {
+                    return Ok(());
+                }
+
                 // Send auto-reply
                 let dm_message = data
                     .config
                     .dm_message
-                    .as_ref()
-                    .map(|s| s.as_str())
+                    .as_deref()
                     .unwrap_or("I'm just a bot :'(. I can't answer to you.");
 
                 let _ = new_message.channel_id.say(ctx, dm_message).await;
@@ -127,6 +142,16 @@ pub async fn event_handler(
                 banned_user.name,
                 guild_id
             );
+
+            if let Err(e) = mod_log::record_external_ban(ctx, data, *guild_id, banned_user).await {
+                tracing::warn!("Failed to record ban in guild {}: {}", guild_id, e);
+            }
+        }
+
+        serenity::FullEvent::InteractionCreate {
+            interaction: serenity::Interaction::Component(press),
+        } => {
+            appeals::handle_component(ctx, data, press).await?;
         }
 
         serenity::FullEvent::Resume { .. } => {
@@ -0,0 +1,182 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::database::ModAction;
+use poise::serenity_prelude as serenity;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum ExportFormat {
+    #[name = "csv"]
+    Csv,
+    #[name = "json"]
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// One exported action, also the column order of the CSV
+#[derive(Serialize)]
+struct ExportRow<'a> {
+    case: i64,
+    date: String,
+    action: &'a str,
+    target_id: String,
+    target_name: Option<String>,
+    moderator_id: String,
+    moderator_name: Option<String>,
+    reason: Option<&'a str>,
+    duration_seconds: Option<i64>,
+}
+
+const CSV_HEADER: &str =
+    "case,date,action,target_id,target_name,moderator_id,moderator_name,reason,duration_seconds\n";
+
+/// Escape a CSV field. Quotes fields that need it, and defuses values a spreadsheet
+/// would otherwise run as a formula.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Write actions as CSV or JSON, with usernames for users the cache knows.
+/// CSV rows are written one at a time as they're built.
+pub fn write(
+    actions: &[ModAction],
+    format: ExportFormat,
+    cache: &serenity::Cache,
+    mut out: impl std::io::Write,
+) -> std::io::Result<()> {
+    let user_name = |id: u64| match id {
+        0 => None,
+        id => cache.user(serenity::UserId::new(id)).map(|u| u.name.clone()),
+    };
+
+    let rows = actions.iter().map(|action| ExportRow {
+        case: action.case_number,
+        date: chrono::DateTime::from_timestamp(action.timestamp, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+        action: &action.action,
+        // IDs as strings, spreadsheets round large numbers
+        target_id: action.target_id.to_string(),
+        target_name: user_name(action.target_id),
+        moderator_id: action.moderator_id.to_string(),
+        moderator_name: user_name(action.moderator_id),
+        reason: action.reason.as_deref(),
+        duration_seconds: action.duration,
+    });
+
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &rows.collect::<Vec<_>>())?;
+        }
+        ExportFormat::Csv => {
+            out.write_all(CSV_HEADER.as_bytes())?;
+            for row in rows {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    row.case,
+                    csv_field(&row.date),
+                    csv_field(row.action),
+                    csv_field(&row.target_id),
+                    csv_field(row.target_name.as_deref().unwrap_or_default()),
+                    csv_field(&row.moderator_id),
+                    csv_field(row.moderator_name.as_deref().unwrap_or_default()),
+                    csv_field(row.reason.unwrap_or_default()),
+                    row.duration_seconds.map(|d| d.to_string()).unwrap_or_default(),
+                )?;
+            }
+        }
+    }
+
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_quotes_when_needed() {
+        assert_eq!(csv_field("plain reason"), "plain reason");
+        assert_eq!(csv_field("spam, slurs"), "\"spam, slurs\"");
+        assert_eq!(csv_field("said \"hi\""), r#""said ""hi""""#);
+        assert_eq!(csv_field("line one\nline two"), "\"line one\nline two\"");
+        assert_eq!(csv_field("carriage\rreturn"), "\"carriage\rreturn\"");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn csv_field_defuses_formulas() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("a=b"), "a=b");
+    }
+
+    #[test]
+    fn csv_export_writes_one_line_per_action() {
+        let actions = vec![
+            ModAction {
+                case_number: 1,
+                timestamp: 1_704_067_200,
+                ..ModAction::new(1, 2, 3, "ban", Some("spam, \"ads\"\nand more"))
+            },
+            ModAction {
+                case_number: 2,
+                timestamp: 1_704_067_200,
+                duration: Some(600),
+                ..ModAction::new(1, 2, 3, "timeout", None)
+            },
+        ];
+
+        let mut out = Vec::new();
+        write(&actions, ExportFormat::Csv, &serenity::Cache::new(), &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            csv,
+            format!(
+                "{}{}{}",
+                CSV_HEADER,
+                "1,2024-01-01T00:00:00+00:00,ban,3,,2,,\"spam, \"\"ads\"\"\nand more\",\n",
+                "2,2024-01-01T00:00:00+00:00,timeout,3,,2,,,600\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,182 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::database::{Database, ModAction};
+use crate::utils::{format_duration, truncate};
+use crate::Data;
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+// How long a ban the bot is about to issue is kept from being recorded as an outside ban
+const PENDING_BAN_TTL: Duration = Duration::from_secs(60);
+
+// Bans the bot is issuing, keyed by (guild, user), which record themselves once they go through
+static PENDING_BANS: LazyLock<Mutex<HashMap<(u64, u64), Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn action_color(action: &str) -> u32 {
+    match action {
+        "ban" | "tempban" => 0xff0000,
+        "kick" | "softban" => 0xff8c00,
+        "timeout" => 0xffd700,
+        "warn" => 0xffa500,
+        "unban" => 0x00c853,
+        _ => 0xff69b4,
+    }
+}
+
+/// Build the mod-log embed for a recorded action
+fn action_embed(action: &ModAction) -> serenity::CreateEmbed {
+    let moderator = if action.moderator_id == 0 {
+        "Unknown".to_string()
+    } else {
+        format!("<@{}>", action.moderator_id)
+    };
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("{} | Case #{}", action.action, action.case_number))
+        .color(action_color(&action.action))
+        .field("User", format!("<@{}> ({})", action.target_id, action.target_id), true)
+        .field("Moderator", moderator, true);
+
+    if let Some(duration) = action.duration {
+        embed = embed.field("Duration", format_duration(duration), true);
+    }
+
+    embed
+        .field(
+            "Reason",
+            truncate(action.reason.as_deref().unwrap_or("No reason given"), 1000),
+            false,
+        )
+        .timestamp(
+            serenity::Timestamp::from_unix_timestamp(action.timestamp)
+                .unwrap_or_else(|_| serenity::Timestamp::now()),
+        )
+}
+
+/// Record a moderation action and post it to the guild's mod-log channel, returning its case number
+pub async fn record(
+    http: &serenity::Http,
+    db: &Database,
+    mut action: ModAction,
+) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+    action.case_number = db.add_mod_action(&action).await?;
+    post(http, db, action.guild_id, action_embed(&action)).await?;
+
+    Ok(action.case_number)
+}
+
+/// Post an embed to the guild's mod-log channel, if one is set
+pub async fn post(
+    http: &serenity::Http,
+    db: &Database,
+    guild_id: u64,
+    embed: serenity::CreateEmbed,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(channel_id) = db.get_mod_log_channel(guild_id).await? {
+        if let Err(e) = serenity::ChannelId::new(channel_id)
+            .send_message(http, serenity::CreateMessage::new().embed(embed))
+            .await
+        {
+            tracing::warn!("Failed to post to mod-log in guild {}: {}", guild_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Note that the bot is about to ban a user, so the ban event doesn't record it a second time
+pub async fn expect_ban(guild_id: serenity::GuildId, user_id: serenity::UserId) {
+    let mut pending = PENDING_BANS.lock().await;
+    pending.retain(|_, since| since.elapsed() < PENDING_BAN_TTL);
+    pending.insert((guild_id.get(), user_id.get()), Instant::now());
+}
+
+/// Whether a ban was announced through `expect_ban`, forgetting it either way
+async fn take_expected_ban(guild_id: serenity::GuildId, user_id: serenity::UserId) -> bool {
+    PENDING_BANS
+        .lock()
+        .await
+        .remove(&(guild_id.get(), user_id.get()))
+        .is_some_and(|since| since.elapsed() < PENDING_BAN_TTL)
+}
+
+/// Record a ban made outside the bot, attributing it through the audit log when possible
+pub async fn record_external_ban(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Bans issued by the bot are recorded by whatever issued them. The event can arrive
+    // before that record is written, so this can't be told from the database alone.
+    if take_expected_ban(guild_id, user.id).await {
+        return Ok(());
+    }
+
+    let bot_user_id = ctx.cache.current_user().id;
+    let now = chrono::Utc::now().timestamp();
+
+    let entry = guild_id
+        .audit_logs(
+            &ctx.http,
+            Some(serenity::audit_log::Action::Member(serenity::audit_log::MemberAction::BanAdd)),
+            None,
+            None,
+            Some(10),
+        )
+        .await
+        .ok()
+        .and_then(|logs| {
+            logs.entries
+                .into_iter()
+                .find(|e| e.target_id.map(|t| t.get()) == Some(user.id.get()))
+                // An older entry belongs to an earlier ban of the same user
+                .filter(|e| now - e.id.created_at().unix_timestamp() <= 60)
+        });
+
+    let (moderator_id, reason, audit_entry_id) = match entry {
+        // Our own bans are recorded by whatever issued them
+        Some(e) if e.user_id == bot_user_id => return Ok(()),
+        Some(e) => (e.user_id.get(), e.reason, Some(e.id.get())),
+        None => {
+            // Without the audit log, skip bans we just recorded ourselves
+            if data.db.has_recent_mod_action(guild_id.get(), user.id.get(), "ban", now - 60).await? {
+                return Ok(());
+            }
+            (0, None, None)
+        }
+    };
+
+    record(
+        &ctx.http,
+        &data.db,
+        ModAction {
+            audit_entry_id,
+            ..ModAction::new(guild_id.get(), moderator_id, user.id.get(), "ban", reason.as_deref())
+        },
+    )
+    .await?;
+
+    Ok(())
+}
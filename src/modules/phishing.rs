@@ -0,0 +1,261 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::Mutex;
+
+// How often the list file is checked for changes
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Domains phishing sites like to imitate
+const PROTECTED_DOMAINS: &[&str] = &[
+    "discord.com",
+    "discord.gg",
+    "discord.gift",
+    "discord.media",
+    "discordapp.com",
+    "discordapp.net",
+    "discordstatus.com",
+    "steamcommunity.com",
+    "steampowered.com",
+];
+
+static PHISHING_LIST: LazyLock<Mutex<PhishingList>> = LazyLock::new(|| Mutex::new(PhishingList::default()));
+
+#[derive(Default)]
+struct PhishingList {
+    path: String,
+    // Hostnames from the list file, matched exactly
+    domains: HashSet<String>,
+    modified: Option<SystemTime>,
+    last_checked: Option<Instant>,
+}
+
+impl PhishingList {
+    /// Reload the list if the file changed since it was last read
+    async fn refresh(&mut self, path: &str) {
+        if self.path == path
+            && self
+                .last_checked
+                .is_some_and(|checked| checked.elapsed() < RELOAD_CHECK_INTERVAL)
+        {
+            return;
+        }
+        self.last_checked = Some(Instant::now());
+
+        let modified = match tokio::fs::metadata(path).await {
+            Ok(metadata) => metadata.modified().ok(),
+            Err(_) => {
+                if !self.domains.is_empty() || self.path != path {
+                    tracing::warn!("Phishing domain list {} not found, phishing detection disabled", path);
+                }
+                self.path = path.to_string();
+                self.domains.clear();
+                self.modified = None;
+                return;
+            }
+        };
+
+        if self.path == path && modified.is_some() && modified == self.modified {
+            return;
+        }
+
+        match tokio::fs::read_to_string(path).await {
+            Ok(contents) => {
+                self.domains = parse_list(&contents);
+                self.modified = modified;
+                self.path = path.to_string();
+                tracing::info!("Loaded {} phishing domains from {}", self.domains.len(), path);
+            }
+            Err(e) => tracing::error!("Failed to read phishing domain list {}: {}", path, e),
+        }
+    }
+}
+
+/// Parse a list file: one domain or URL per line, `#` starts a comment
+fn parse_list(contents: &str) -> HashSet<String> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let line = line.split_once("://").map(|(_, rest)| rest).unwrap_or(line);
+            let host = line.split(['/', '?', ':']).next().unwrap_or(line);
+            clean_host(host.trim_start_matches("*."))
+        })
+        .filter(|domain| domain.contains('.'))
+        .collect()
+}
+
+/// Lowercase a hostname and drop the trailing dot and `www.` prefix
+fn clean_host(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_lowercase();
+    match host.strip_prefix("www.") {
+        Some(rest) => rest.to_string(),
+        None => host,
+    }
+}
+
+/// Fold a hostname to a skeleton so lookalikes compare equal, e.g. `dlscоrd.com`
+/// (Cyrillic `о`) and `discord.com` both become `dlscord.com`
+pub fn normalize_host(host: &str) -> String {
+    let folded: String = clean_host(host)
+        .chars()
+        .filter(|c| !matches!(*c as u32, 0x0300..=0x036F | 0x200B..=0x200D | 0xFEFF))
+        .map(fold_confusable)
+        .collect();
+
+    folded.replace("rn", "m").replace("vv", "w")
+}
+
+fn fold_confusable(c: char) -> char {
+    match c {
+        'а' | 'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ɑ' | 'α' | '4' => 'a',
+        'Ь' | 'ь' | 'в' | 'β' => 'b',
+        'с' | 'ç' | 'ϲ' => 'c',
+        'ԁ' | 'ɗ' => 'd',
+        'е' | 'è' | 'é' | 'ê' | 'ë' | 'ε' | '3' => 'e',
+        'ɡ' | 'ġ' | '9' => 'g',
+        'һ' => 'h',
+        'i' | 'l' | '1' | '|' | 'ı' | 'ì' | 'í' | 'î' | 'ï' | 'і' | 'ӏ' | 'ι' | 'ĺ' | 'ł' => 'l',
+        'ј' => 'j',
+        'κ' | 'к' => 'k',
+        'м' => 'm',
+        'ո' | 'ñ' | 'η' | 'п' => 'n',
+        'о' | 'ο' | 'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | '0' => 'o',
+        'р' | 'ρ' => 'p',
+        'ԛ' => 'q',
+        'г' => 'r',
+        'ѕ' | '5' | '$' => 's',
+        'т' | 'τ' | '7' => 't',
+        'υ' | 'ù' | 'ú' | 'û' | 'ü' | 'μ' => 'u',
+        'ν' | 'ѵ' => 'v',
+        'ԝ' | 'ω' | 'ш' => 'w',
+        'х' | 'χ' => 'x',
+        'у' | 'ý' | 'ÿ' | 'γ' => 'y',
+        'ᴢ' | '2' => 'z',
+        c => c,
+    }
+}
+
+/// Whether a host is a protected domain or a subdomain of one
+fn is_protected(host: &str) -> bool {
+    PROTECTED_DOMAINS.iter().any(|protected| {
+        host == *protected
+            || host
+                .strip_suffix(protected)
+                .is_some_and(|sub| sub.ends_with('.'))
+    })
+}
+
+/// Whether a host is on the list or imitates a protected domain
+fn is_phishing_host(domains: &HashSet<String>, host: &str) -> bool {
+    if is_protected(host) {
+        return false;
+    }
+
+    let labels: Vec<&str> = host.split('.').collect();
+
+    // Check the host and every parent domain, e.g. a.b.evil.com -> b.evil.com -> evil.com
+    (0..labels.len().saturating_sub(1)).any(|start| {
+        let domain = labels[start..].join(".");
+        if domains.contains(&domain) {
+            return true;
+        }
+
+        let skeleton = normalize_host(&domain);
+        PROTECTED_DOMAINS
+            .iter()
+            .any(|protected| normalize_host(protected) == skeleton)
+    })
+}
+
+/// Check hostnames against the phishing list and for lookalikes of protected domains,
+/// returning the first offending host
+pub async fn find_phishing_host(list_path: &str, hosts: &[String]) -> Option<String> {
+    let mut list = PHISHING_LIST.lock().await;
+    list.refresh(list_path).await;
+
+    hosts
+        .iter()
+        .map(|host| clean_host(host))
+        .find(|host| is_phishing_host(&list.domains, host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(domains: &[&str]) -> HashSet<String> {
+        parse_list(&domains.join("\n"))
+    }
+
+    #[test]
+    fn normalize_host_folds_lookalikes() {
+        assert_eq!(normalize_host("discord.com"), "dlscord.com");
+        assert_eq!(normalize_host("DISCORD.COM."), "dlscord.com");
+        assert_eq!(normalize_host("www.discord.com"), "dlscord.com");
+        assert_eq!(normalize_host("d1sc\u{43e}rd.com"), "dlscord.com");
+        assert_eq!(normalize_host("steamcommunlty.com"), "steamcommunlty.com");
+        assert_eq!(normalize_host("steamcornmunity.com"), "steamcommunlty.com");
+        assert_eq!(normalize_host("disc\u{200b}ord.com"), "dlscord.com");
+    }
+
+    #[test]
+    fn list_matches_exact_hosts_and_subdomains() {
+        let domains = list(&["https://free-nitro.ru/claim", "*.evil.com", "# comment", "gift.xyz # inline"]);
+        assert!(is_phishing_host(&domains, "free-nitro.ru"));
+        assert!(is_phishing_host(&domains, "a.b.evil.com"));
+        assert!(is_phishing_host(&domains, "gift.xyz"));
+        assert!(!is_phishing_host(&domains, "free-nitro.com"));
+        assert!(!is_phishing_host(&domains, "notevil.com"));
+    }
+
+    #[test]
+    fn list_entries_never_match_protected_domains() {
+        let domains = list(&["d1scord.com", "steamcommunlty.com", "discord.com"]);
+        for host in ["discord.com", "www.discord.com", "cdn.discord.com", "steamcommunity.com"] {
+            assert!(!is_phishing_host(&domains, &clean_host(host)), "{} was flagged", host);
+        }
+        assert!(is_phishing_host(&domains, "d1scord.com"));
+        assert!(is_phishing_host(&domains, "steamcommunlty.com"));
+    }
+
+    #[test]
+    fn lookalikes_of_protected_domains_are_flagged() {
+        let domains = HashSet::new();
+        assert!(is_phishing_host(&domains, "d1scord.com"));
+        assert!(is_phishing_host(&domains, "login.disc\u{43e}rd.gg"));
+        assert!(is_phishing_host(&domains, "steamcornmunity.com"));
+        assert!(!is_phishing_host(&domains, "discordmerch.com"));
+        assert!(!is_phishing_host(&domains, "example.com"));
+    }
+
+    #[test]
+    fn protected_domains_and_subdomains_are_safe() {
+        let domains = HashSet::new();
+        assert!(is_protected("discord.com"));
+        assert!(is_protected("media.discordapp.net"));
+        assert!(!is_protected("notdiscord.com"));
+        assert!(!is_phishing_host(&domains, &clean_host("www.discord.com")));
+        assert!(!is_phishing_host(&domains, &clean_host("WWW.Steampowered.com.")));
+    }
+}
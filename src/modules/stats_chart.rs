@@ -0,0 +1,273 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use crate::database::ModStats;
+use std::collections::HashMap;
+
+const WIDTH: usize = 800;
+const HEIGHT: usize = 580;
+// Each font pixel is drawn as a SCALE x SCALE block
+const SCALE: usize = 2;
+const GLYPH_ADVANCE: usize = 6 * SCALE;
+
+const BACKGROUND: u32 = 0x2b2d31;
+const FOREGROUND: u32 = 0xdbdee1;
+const GRID: u32 = 0x404249;
+const ACCENT: u32 = 0xff69b4;
+
+// Only the most recent days fit on the daily chart
+const MAX_DAYS: usize = 90;
+const TOP_MODS: usize = 5;
+const MAX_NAME_CHARS: usize = 14;
+
+// Daily chart plot area
+const PLOT_LEFT: usize = 70;
+const PLOT_TOP: usize = 96;
+const PLOT_WIDTH: usize = WIDTH - PLOT_LEFT - 20;
+const PLOT_HEIGHT: usize = 200;
+
+// Top moderators chart
+const MODS_TOP: usize = 370;
+const MODS_ROW: usize = 38;
+const MODS_BAR_LEFT: usize = 200;
+const MODS_BAR_WIDTH: usize = WIDTH - MODS_BAR_LEFT - 90;
+
+/// Mod-log colors share hues between related actions, stacked bars need distinct ones
+fn action_color(action: &str) -> u32 {
+    match action {
+        "ban" => 0xe53935,
+        "tempban" => 0x8e24aa,
+        "softban" => 0xff7043,
+        "kick" => 0xfb8c00,
+        "timeout" => 0xfdd835,
+        "warn" => 0x42a5f5,
+        "unban" => 0x43a047,
+        _ => 0x9e9e9e,
+    }
+}
+
+/// 5x7 glyphs, one byte per row with the leftmost pixel in bit 4
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        ' ' => [0x00; 7],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+fn text_width(text: &str) -> usize {
+    (text.chars().count() * GLYPH_ADVANCE).saturating_sub(SCALE)
+}
+
+/// An RGB image we draw rectangles and text into
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(color: u32) -> Self {
+        let [_, r, g, b] = color.to_be_bytes();
+        Self {
+            pixels: [r, g, b].repeat(WIDTH * HEIGHT),
+        }
+    }
+
+    fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: u32) {
+        let [_, r, g, b] = color.to_be_bytes();
+        for row in y.min(HEIGHT)..(y + height).min(HEIGHT) {
+            for col in x.min(WIDTH)..(x + width).min(WIDTH) {
+                let i = (row * WIDTH + col) * 3;
+                self.pixels[i..i + 3].copy_from_slice(&[r, g, b]);
+            }
+        }
+    }
+
+    fn text(&mut self, x: usize, y: usize, text: &str, color: u32) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i * GLYPH_ADVANCE;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0x10 >> col) != 0 {
+                        self.fill_rect(left + col * SCALE, y + row * SCALE, SCALE, SCALE, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut output = Vec::new();
+        let mut encoder = png::Encoder::new(&mut output, WIDTH as u32, HEIGHT as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+
+        Ok(output)
+    }
+}
+
+/// Render actions per day stacked by type, and the top moderators, as a PNG
+pub fn render_mod_stats(
+    stats: &ModStats,
+    moderator_name: impl Fn(&str) -> String,
+) -> Result<Vec<u8>, png::EncodingError> {
+    let mut canvas = Canvas::new(BACKGROUND);
+
+    // Stack the most common actions at the bottom
+    let mut actions: Vec<(&str, i64)> = stats
+        .action_counts
+        .iter()
+        .map(|(action, count)| (action.as_str(), *count))
+        .collect();
+    actions.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    // Every day from the first action to the last, including quiet ones
+    let mut per_day: HashMap<chrono::NaiveDate, HashMap<&str, i64>> = HashMap::new();
+    for (day, action, count) in &stats.daily {
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+            per_day.entry(date).or_default().insert(action.as_str(), *count);
+        }
+    }
+    let days: Vec<chrono::NaiveDate> = match (per_day.keys().min(), per_day.keys().max()) {
+        (Some(&first), Some(&last)) => {
+            let first = first.max(last - chrono::Duration::days(MAX_DAYS as i64 - 1));
+            first.iter_days().take_while(|d| *d <= last).collect()
+        }
+        _ => Vec::new(),
+    };
+
+    canvas.text(20, 16, "Actions per day", FOREGROUND);
+
+    // Legend, wrapping onto a second row when there are many action types
+    let (mut legend_x, mut legend_y) = (20, 44);
+    for (action, _) in &actions {
+        let entry_width = 18 + text_width(action) + 20;
+        if legend_x + entry_width > WIDTH {
+            legend_x = 20;
+            legend_y += 20;
+        }
+        canvas.fill_rect(legend_x, legend_y, 12, 12, action_color(action));
+        canvas.text(legend_x + 18, legend_y, action, FOREGROUND);
+        legend_x += entry_width;
+    }
+
+    let day_total = |day: &chrono::NaiveDate| per_day.get(day).map_or(0, |counts| counts.values().sum::<i64>());
+    let max_total = days.iter().map(day_total).max().unwrap_or(0).max(1);
+
+    // Axis labels and grid lines at zero, half and the maximum
+    for (value, y) in [
+        (max_total, PLOT_TOP),
+        (max_total / 2, PLOT_TOP + PLOT_HEIGHT / 2),
+        (0, PLOT_TOP + PLOT_HEIGHT),
+    ] {
+        canvas.fill_rect(PLOT_LEFT, y, PLOT_WIDTH, 1, GRID);
+        let label = value.to_string();
+        canvas.text(PLOT_LEFT - 10 - text_width(&label), y.saturating_sub(7), &label, FOREGROUND);
+    }
+
+    if !days.is_empty() {
+        let slot = PLOT_WIDTH / days.len();
+        let bar_width = if slot > 3 { slot - slot / 4 } else { slot.max(1) };
+
+        for (i, day) in days.iter().enumerate() {
+            let x = PLOT_LEFT + i * slot + (slot - bar_width) / 2;
+            let mut top = PLOT_TOP + PLOT_HEIGHT;
+            for (action, _) in &actions {
+                let count = per_day.get(day).and_then(|c| c.get(action)).copied().unwrap_or(0);
+                let height = (count as usize * PLOT_HEIGHT)
+                    .div_ceil(max_total as usize)
+                    .min(top - PLOT_TOP);
+                top -= height;
+                canvas.fill_rect(x, top, bar_width, height, action_color(action));
+            }
+        }
+
+        let first = days[0].format("%Y-%m-%d").to_string();
+        let last = days[days.len() - 1].format("%Y-%m-%d").to_string();
+        canvas.text(PLOT_LEFT, PLOT_TOP + PLOT_HEIGHT + 10, &first, FOREGROUND);
+        if days.len() > 1 {
+            canvas.text(
+                PLOT_LEFT + PLOT_WIDTH - text_width(&last),
+                PLOT_TOP + PLOT_HEIGHT + 10,
+                &last,
+                FOREGROUND,
+            );
+        }
+    }
+
+    canvas.text(20, MODS_TOP - 36, "Top moderators", FOREGROUND);
+
+    let top_mods = &stats.top_mods[..stats.top_mods.len().min(TOP_MODS)];
+    let max_count = top_mods.first().map_or(1, |(_, count)| (*count).max(1));
+    for (i, (mod_id, count)) in top_mods.iter().enumerate() {
+        let y = MODS_TOP + i * MODS_ROW;
+        let name: String = moderator_name(mod_id).chars().take(MAX_NAME_CHARS).collect();
+        canvas.text(20, y + 6, &name, FOREGROUND);
+
+        let width = (*count as usize * MODS_BAR_WIDTH / max_count as usize).max(2);
+        canvas.fill_rect(MODS_BAR_LEFT, y, width, 26, ACCENT);
+        canvas.text(MODS_BAR_LEFT + width + 10, y + 6, &count.to_string(), FOREGROUND);
+    }
+
+    canvas.encode()
+}
+
@@ -0,0 +1,149 @@
+/*
+    Yuno Gasai - A Discord bot with moderation, auto-cleaning, and utility features.
+    Copyright (C) 2018 Maeeen <maeeennn@gmail.com>
+    Copyright (C) 2025 blubskye
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use super::mod_log;
+use crate::database::{Database, ModAction};
+use poise::serenity_prelude as serenity;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Discord's JSON error code for "Unknown Ban".
+const UNKNOWN_BAN: isize = 10026;
+
+lazy_static::lazy_static! {
+    static ref TEMP_BANS_RUNNING: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Start the unban scheduler. Expirations live in the database, so bans that
+/// ran out while the bot was offline are lifted on the first tick.
+pub async fn start(ctx: serenity::Context, db: Database) {
+    let mut running = TEMP_BANS_RUNNING.lock().await;
+    if *running {
+        return;
+    }
+    *running = true;
+    drop(running);
+
+    let ctx = Arc::new(ctx);
+    let db = Arc::new(db);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = tick(&ctx, &db).await {
+                tracing::error!("Temp ban scheduler error: {}", e);
+            }
+        }
+    });
+
+    tracing::info!("Temp ban scheduler started");
+}
+
+/// Lift a ban and record the unban, returning its case number.
+/// Used by `/unban` and by accepted ban appeals.
+pub async fn lift_ban(
+    http: &serenity::Http,
+    db: &Database,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    moderator_id: u64,
+    reason: Option<&str>,
+) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+    guild_id.unban(http, user_id).await?;
+    db.remove_temp_ban(guild_id.get(), user_id.get()).await?;
+
+    mod_log::record(
+        http,
+        db,
+        ModAction::new(guild_id.get(), moderator_id, user_id.get(), "unban", reason),
+    )
+    .await
+}
+
+async fn tick(ctx: &serenity::Context, db: &Database) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let expired = db.get_expired_temp_bans(chrono::Utc::now().timestamp()).await?;
+
+    for ban in expired {
+        let guild_id = serenity::GuildId::new(ban.guild_id);
+        let user_id = serenity::UserId::new(ban.user_id);
+
+        match guild_id.unban(&ctx.http, user_id).await {
+            Ok(()) => {
+                // The unban went through, so the temp ban is dropped below even if recording fails
+                let bot_user_id = ctx.cache.current_user().id.get();
+                if let Err(e) = mod_log::record(
+                    &ctx.http,
+                    db,
+                    ModAction::new(ban.guild_id, bot_user_id, ban.user_id, "unban", Some("Temporary ban expired")),
+                )
+                .await
+                {
+                    tracing::error!(
+                        "Failed to record expired temporary ban of {} in guild {}: {}",
+                        ban.user_id,
+                        ban.guild_id,
+                        e
+                    );
+                }
+
+                tracing::info!(
+                    "Lifted temporary ban of {} in guild {} (banned by {})",
+                    ban.user_id,
+                    ban.guild_id,
+                    ban.moderator_id
+                );
+            }
+            // Already unbanned, so there's nothing left to do
+            Err(serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(ref response)))
+                if response.status_code == serenity::StatusCode::NOT_FOUND
+                    || response.error.code == UNKNOWN_BAN =>
+            {
+                tracing::warn!(
+                    "Dropping temporary ban of {} in guild {}: {}",
+                    ban.user_id,
+                    ban.guild_id,
+                    response.error.message
+                );
+            }
+            // Missing permissions and rate limits can clear up, retry on the next tick
+            Err(serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(ref response))) => {
+                tracing::warn!(
+                    "Failed to lift temporary ban of {} in guild {} ({}): {}",
+                    ban.user_id,
+                    ban.guild_id,
+                    response.status_code,
+                    response.error.message
+                );
+                continue;
+            }
+            // Anything else may be transient, retry on the next tick
+            Err(e) => {
+                tracing::warn!("Failed to lift temporary ban of {} in guild {}: {}", ban.user_id, ban.guild_id, e);
+                continue;
+            }
+        }
+
+        db.remove_temp_ban(ban.guild_id, ban.user_id).await?;
+    }
+
+    Ok(())
+}
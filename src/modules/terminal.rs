@@ -17,7 +17,9 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use super::mod_export::{self, ExportFormat};
 use crate::database::{BotBan, Database};
+use crate::utils::parse_time_bound;
 use poise::serenity_prelude as serenity;
 use std::io::{self, BufRead, Write};
 use std::sync::Arc;
@@ -83,6 +85,7 @@ async fn process_command(
         "botunban" => handle_bot_unban(db, args).await?,
         "botbanlist" => show_bot_ban_list(db).await?,
         "status" => show_status(ctx, db).await?,
+        "modexport" => handle_mod_export(ctx, db, args).await?,
         "quit" | "exit" => {
             println!("Shutting down...");
             std::process::exit(0);
@@ -103,6 +106,7 @@ Available Commands:
   botunban    - Unban user from bot (botunban <user_id>)
   botbanlist  - List all bot-banned users
   status      - Show bot status
+  modexport   - Export moderation history (modexport <guild_id> [csv|json] [since|all] [path])
   quit/exit   - Shutdown the bot
 "#
     );
@@ -239,6 +243,60 @@ async fn show_bot_ban_list(db: &Database) -> Result<(), Box<dyn std::error::Erro
     Ok(())
 }
 
+async fn handle_mod_export(
+    ctx: &serenity::Context,
+    db: &Database,
+    args: &[&str],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if args.is_empty() {
+        println!("Usage: modexport <guild_id> [csv|json] [since|all] [path]");
+        return Ok(());
+    }
+
+    let guild_id: u64 = match args[0].parse() {
+        Ok(id) => id,
+        Err(_) => {
+            println!("Invalid guild ID.");
+            return Ok(());
+        }
+    };
+
+    let format = match args.get(1).map(|f| f.to_lowercase()).as_deref() {
+        None | Some("csv") => ExportFormat::Csv,
+        Some("json") => ExportFormat::Json,
+        Some(other) => {
+            println!("Unknown format: {}. Use csv or json.", other);
+            return Ok(());
+        }
+    };
+
+    // A date like 2025-01-31 or how long ago, e.g. 30d
+    let since = match args.get(2).copied() {
+        None | Some("all") => None,
+        Some(since) => match parse_time_bound(since, false) {
+            Some(timestamp) => Some(timestamp),
+            None => {
+                println!("Invalid since: {}. Use a date like 2025-01-31, a duration like 30d, or all.", since);
+                return Ok(());
+            }
+        },
+    };
+
+    let path = args
+        .get(3)
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| format!("mod-actions-{}.{}", guild_id, format.extension()));
+
+    let actions = db.get_mod_actions(guild_id, since).await?;
+    let mut output = Vec::new();
+    mod_export::write(&actions, format, &ctx.cache, &mut output)?;
+    tokio::fs::write(&path, output).await?;
+
+    println!("Exported {} moderation action(s) to {}", actions.len(), path);
+
+    Ok(())
+}
+
 async fn show_status(
     ctx: &serenity::Context,
     db: &Database,
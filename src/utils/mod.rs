@@ -17,22 +17,426 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-/// Format a duration in seconds to a human-readable string
+use crate::Context;
+use poise::serenity_prelude as serenity;
+
+/// Longest timeout Discord allows, 28 days
+pub const MAX_TIMEOUT_SECS: i64 = 28 * 86400;
+
+const DURATION_UNITS: &[(&str, i64)] = &[("w", 604800), ("d", 86400), ("h", 3600), ("m", 60), ("s", 1)];
+
+/// Format a duration in seconds to a human-readable string, e.g. "1d 2h 30m".
+/// The output parses back into the same [`HumanDuration`].
 pub fn format_duration(seconds: i64) -> String {
-    let hours = seconds / 3600;
-    let minutes = (seconds % 3600) / 60;
-    let secs = seconds % 60;
-
-    if hours > 0 {
-        format!("{}h {}m {}s", hours, minutes, secs)
-    } else if minutes > 0 {
-        format!("{}m {}s", minutes, secs)
+    let mut remaining = seconds.max(0);
+    let mut parts = Vec::new();
+
+    for (unit, size) in DURATION_UNITS {
+        if remaining >= *size {
+            parts.push(format!("{}{}", remaining / size, unit));
+            remaining %= size;
+        }
+    }
+
+    if parts.is_empty() {
+        "0s".to_string()
     } else {
-        format!("{}s", secs)
+        parts.join(" ")
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DurationParseError {
+    #[error("no duration given")]
+    Empty,
+    #[error("couldn't understand `{0}`, try something like `1d2h30m`, `90s`, `2w` or `PT1H30M`")]
+    Invalid(String),
+    #[error("`{0}` is too long")]
+    Overflow(String),
+}
+
+/// A duration given by a user. Accepts unit lists like `1d2h30m`, `90s`, `2w` or `1h 30m`,
+/// ISO-8601 durations like `PT1H30M`, and bare numbers as minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HumanDuration {
+    seconds: i64,
+}
+
+impl HumanDuration {
+    pub fn from_secs(seconds: i64) -> Self {
+        Self { seconds }
+    }
+
+    pub fn as_secs(self) -> i64 {
+        self.seconds
+    }
+
+    pub fn as_minutes(self) -> i64 {
+        self.seconds / 60
+    }
+
+    pub fn as_chrono(self) -> chrono::Duration {
+        chrono::Duration::seconds(self.seconds)
+    }
+}
+
+impl std::fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format_duration(self.seconds))
+    }
+}
+
+impl std::str::FromStr for HumanDuration {
+    type Err = DurationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_lowercase();
+        if input.is_empty() {
+            return Err(DurationParseError::Empty);
+        }
+
+        let seconds = if input.chars().all(|c| c.is_ascii_digit()) {
+            input
+                .parse::<i64>()
+                .ok()
+                .and_then(|minutes| minutes.checked_mul(60))
+                .ok_or_else(|| DurationParseError::Overflow(s.to_string()))?
+        } else if let Some(iso) = input.strip_prefix('p') {
+            parse_iso8601(iso, s)?
+        } else {
+            parse_units(&input, s)?
+        };
+
+        if seconds < 0 {
+            return Err(DurationParseError::Invalid(s.to_string()));
+        }
+        Ok(Self::from_secs(seconds))
+    }
+}
+
+/// Split `input` into (number, unit) pairs, e.g. "1d 2h" into [(1, "d"), (2, "h")]
+fn split_components(input: &str, original: &str) -> Result<Vec<(i64, String)>, DurationParseError> {
+    let invalid = || DurationParseError::Invalid(original.to_string());
+    let mut components = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut number = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+            number.push(c);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut unit = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+            unit.push(c);
+        }
+
+        if number.is_empty() || unit.is_empty() {
+            return Err(invalid());
+        }
+        let number = number
+            .parse()
+            .map_err(|_| DurationParseError::Overflow(original.to_string()))?;
+        components.push((number, unit));
+    }
+
+    if components.is_empty() {
+        return Err(invalid());
+    }
+    Ok(components)
+}
+
+/// Add up components using `unit_size` to get each unit's length in seconds
+fn sum_components(
+    components: &[(i64, String)],
+    unit_size: impl Fn(&str) -> Option<i64>,
+    original: &str,
+) -> Result<i64, DurationParseError> {
+    components.iter().try_fold(0i64, |total, (value, unit)| {
+        let size = unit_size(unit).ok_or_else(|| DurationParseError::Invalid(original.to_string()))?;
+        value
+            .checked_mul(size)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| DurationParseError::Overflow(original.to_string()))
+    })
+}
+
+fn parse_units(input: &str, original: &str) -> Result<i64, DurationParseError> {
+    let components = split_components(input, original)?;
+
+    sum_components(
+        &components,
+        |unit| match unit {
+            "s" | "sec" | "secs" | "second" | "seconds" => Some(1),
+            "m" | "min" | "mins" | "minute" | "minutes" => Some(60),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Some(3600),
+            "d" | "day" | "days" => Some(86400),
+            "w" | "wk" | "wks" | "week" | "weeks" => Some(604800),
+            _ => None,
+        },
+        original,
+    )
+}
+
+/// Parse the part of an ISO-8601 duration after the leading `P`. Years and
+/// months are taken as 365 and 30 days.
+fn parse_iso8601(input: &str, original: &str) -> Result<i64, DurationParseError> {
+    let invalid = || DurationParseError::Invalid(original.to_string());
+    if input.is_empty() || input.contains(|c: char| c.is_whitespace() || c == ',') {
+        return Err(invalid());
+    }
+
+    let (date, time) = match input.split_once('t') {
+        Some((_, "")) => return Err(invalid()),
+        Some((date, time)) => (date, time),
+        None => (input, ""),
+    };
+
+    let mut seconds = 0i64;
+    if !date.is_empty() {
+        let components = split_components(date, original)?;
+        seconds = sum_components(
+            &components,
+            |unit| match unit {
+                "y" => Some(365 * 86400),
+                "m" => Some(30 * 86400),
+                "w" => Some(604800),
+                "d" => Some(86400),
+                _ => None,
+            },
+            original,
+        )?;
+    }
+    if !time.is_empty() {
+        let components = split_components(time, original)?;
+        let time_seconds = sum_components(
+            &components,
+            |unit| match unit {
+                "h" => Some(3600),
+                "m" => Some(60),
+                "s" => Some(1),
+                _ => None,
+            },
+            original,
+        )?;
+        seconds = seconds
+            .checked_add(time_seconds)
+            .ok_or_else(|| DurationParseError::Overflow(original.to_string()))?;
+    }
+
+    Ok(seconds)
+}
+
+/// Parse a date (YYYY-MM-DD) or a duration ago (e.g. 30d) into a timestamp.
+/// Dates resolve to the start of the day, or its end with `end_of_day`.
+pub fn parse_time_bound(input: &str, end_of_day: bool) -> Option<i64> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d") {
+        let start = date.and_hms_opt(0, 0, 0)?.and_utc().timestamp();
+        return Some(if end_of_day { start + 86399 } else { start });
     }
+
+    let ago: HumanDuration = input.parse().ok()?;
+    Some(chrono::Utc::now().timestamp() - ago.as_secs())
 }
 
 /// Check if a string is a valid URL
 pub fn is_url(s: &str) -> bool {
     s.starts_with("http://") || s.starts_with("https://")
 }
+
+/// Shorten a string to at most `max` characters, marking the cut with an ellipsis
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let mut truncated: String = s.chars().take(max.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Send embeds as pages with previous/next buttons
+pub async fn paginate_embeds(ctx: Context<'_>, pages: &[serenity::CreateEmbed]) -> Result<(), serenity::Error> {
+    if pages.len() <= 1 {
+        if let Some(page) = pages.first() {
+            ctx.send(poise::CreateReply::default().embed(page.clone())).await?;
+        }
+        return Ok(());
+    }
+
+    let ctx_id = ctx.id();
+    let prev_button_id = format!("{}prev", ctx_id);
+    let next_button_id = format!("{}next", ctx_id);
+
+    let buttons = serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(&prev_button_id).emoji('◀'),
+        serenity::CreateButton::new(&next_button_id).emoji('▶'),
+    ]);
+    let page_embed = |index: usize| {
+        pages[index]
+            .clone()
+            .footer(serenity::CreateEmbedFooter::new(format!("Page {}/{}", index + 1, pages.len())))
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(page_embed(0))
+            .components(vec![buttons]),
+    )
+    .await?;
+
+    let mut current_page = 0;
+    while let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(std::time::Duration::from_secs(300))
+        .await
+    {
+        if press.data.custom_id == next_button_id {
+            current_page = (current_page + 1) % pages.len();
+        } else if press.data.custom_id == prev_button_id {
+            current_page = current_page.checked_sub(1).unwrap_or(pages.len() - 1);
+        } else {
+            continue;
+        }
+
+        press
+            .create_response(
+                ctx.serenity_context(),
+                serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new().embed(page_embed(current_page)),
+                ),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Ask the command author to confirm with buttons, returning the prompt message if they did
+pub async fn confirm<'a>(
+    ctx: Context<'a>,
+    prompt: &str,
+) -> Result<Option<poise::ReplyHandle<'a>>, serenity::Error> {
+    let ctx_id = ctx.id();
+    let confirm_button_id = format!("{}confirm", ctx_id);
+    let cancel_button_id = format!("{}cancel", ctx_id);
+
+    let buttons = serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(&confirm_button_id)
+            .label("Confirm")
+            .style(serenity::ButtonStyle::Danger),
+        serenity::CreateButton::new(&cancel_button_id)
+            .label("Cancel")
+            .style(serenity::ButtonStyle::Secondary),
+    ]);
+
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .content(prompt)
+                .components(vec![buttons]),
+        )
+        .await?;
+
+    let press = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(std::time::Duration::from_secs(60))
+        .await;
+
+    let confirmed = press
+        .as_ref()
+        .is_some_and(|press| press.data.custom_id == confirm_button_id);
+    let content = match &press {
+        None => ":x: Timed out, nothing was done.",
+        Some(_) if !confirmed => ":x: Cancelled.",
+        Some(_) => prompt,
+    };
+
+    // Remove the buttons either way
+    match press {
+        Some(press) => {
+            press
+                .create_response(
+                    ctx.serenity_context(),
+                    serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content(content)
+                            .components(vec![]),
+                    ),
+                )
+                .await?
+        }
+        None => {
+            reply
+                .edit(ctx, poise::CreateReply::default().content(content).components(vec![]))
+                .await?
+        }
+    }
+
+    Ok(confirmed.then_some(reply))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(input: &str) -> i64 {
+        input.parse::<HumanDuration>().unwrap().as_secs()
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(secs("90s"), 90);
+        assert_eq!(secs("10m"), 600);
+        assert_eq!(secs("2h"), 7200);
+        assert_eq!(secs("1d2h30m"), 86400 + 7200 + 1800);
+        assert_eq!(secs("2w"), 2 * 604800);
+        assert_eq!(secs("1h 30m"), 5400);
+        assert_eq!(secs("1 hour, 30 minutes"), 5400);
+        assert_eq!(secs("3 Days"), 3 * 86400);
+    }
+
+    #[test]
+    fn bare_numbers_are_minutes() {
+        assert_eq!(secs("15"), 900);
+        assert_eq!(secs(" 0 "), 0);
+    }
+
+    #[test]
+    fn iso8601() {
+        assert_eq!(secs("PT1H30M"), 5400);
+        assert_eq!(secs("P1DT12H"), 86400 + 43200);
+        assert_eq!(secs("P2W"), 2 * 604800);
+        assert_eq!(secs("pt45s"), 45);
+    }
+
+    #[test]
+    fn overflow() {
+        for input in ["9999999999999999999999", "999999999999999999m", "153722867280912931w", &i64::MAX.to_string()] {
+            assert!(
+                matches!(input.parse::<HumanDuration>(), Err(DurationParseError::Overflow(_))),
+                "{} didn't overflow",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn bad_input() {
+        assert!(matches!("".parse::<HumanDuration>(), Err(DurationParseError::Empty)));
+        assert!(matches!("   ".parse::<HumanDuration>(), Err(DurationParseError::Empty)));
+        for input in ["abc", "10x", "h", "5m3", "-5", "1.5h", "P", "PT", "P1H", "PT1D", "P1D T1H"] {
+            assert!(
+                matches!(input.parse::<HumanDuration>(), Err(DurationParseError::Invalid(_))),
+                "{} wasn't rejected",
+                input
+            );
+        }
+    }
+}